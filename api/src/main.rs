@@ -1,16 +1,18 @@
+use axum::{routing::get, Router, Server};
 
-use axum::{
-    Router,
-    routing::get,
-    Server
-};
+use core::StrategyRegistry;
 
 #[tokio::main]
 async fn main() {
-    let app = Router::new().route("/", get(|| async { "Hello, World!" }));
+    let app = Router::new()
+        .route("/", get(|| async { "Hello, World!" }))
+        .route(
+            "/strategies",
+            get(|| async { StrategyRegistry::new().describe() }),
+        );
 
     Server::bind(&"0.0.0.0:3000".parse().unwrap())
         .serve(app.into_make_service())
         .await
         .unwrap();
-}
\ No newline at end of file
+}
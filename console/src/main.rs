@@ -1,16 +1,19 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::BufReader,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use core::game_master::GameMasterBuilder;
-use core::options::Options;
-use core::strategy::Strategy;
+use core::{
+    DeckSpec, GameMasterBuilder, GameSetup, GeneticTuner, Heuristic, LearningRates, Options,
+    QTable, QTrainer, Rules, StrategyFactory, StrategyRegistry, DEFAULT_WEIGHTS, FEATURES,
+    FEATURE_COUNT,
+};
 
+use clap::{Parser, Subcommand};
 use serde::Deserialize;
-use serde_json::from_reader;
-use clap::Parser;
+use serde_json::{from_reader, json, Map, Value};
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -28,12 +31,141 @@ struct Args {
 
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
+
+    /// A rules preset, replacing the one in the config
+    #[arg(short, long)]
+    rules: Option<String>,
+
+    /// Overrides a single rule, such as plus_stacking=liberal
+    #[arg(long = "set", value_name = "FIELD=VALUE")]
+    overrides: Vec<String>,
+
+    /// Deals and shuffles every game the same way on every run
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Lets strategies that support it, like endgame-search, see every hidden card.
+    /// This is cheating and only meant for analysis.
+    #[arg(long, default_value_t = false)]
+    perfect_information: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Inspects the rules presets
+    Rules {
+        #[command(subcommand)]
+        command: RulesCommand,
+    },
+
+    /// Lists every strategy along with its parameters
+    Strategies,
+
+    /// Trains a Q-table by self-play, for the q-policy strategy to play.
+    /// The rules, variant and deck come from the config and the command line.
+    Train {
+        /// Where the learned table is written
+        #[arg(short, long, default_value = "q-policy.txt")]
+        out: String,
+
+        /// Goes on training the table in this file instead of starting from scratch
+        #[arg(long)]
+        resume: Option<String>,
+
+        /// How many self-play games to train for
+        #[arg(short, long, default_value_t = 100_000)]
+        episodes: usize,
+
+        /// How many learners play every game
+        #[arg(long, default_value_t = 2)]
+        players: usize,
+
+        /// How many training games go by between evaluations against naive
+        #[arg(long, default_value_t = 10_000)]
+        eval_every: usize,
+
+        /// How many games every evaluation plays
+        #[arg(long, default_value_t = 1000)]
+        eval_games: usize,
+
+        /// How far every update moves a value towards its target
+        #[arg(long, default_value_t = 0.1)]
+        alpha: f64,
+
+        /// How much the value of the next decision counts towards the one before it
+        #[arg(long, default_value_t = 0.98)]
+        gamma: f64,
+
+        /// The chance of exploring with a random move
+        #[arg(long, default_value_t = 0.1)]
+        epsilon: f64,
+    },
+
+    /// Tunes the weights of the heuristic strategy with a genetic algorithm
+    /// and writes the best weights out as a player config.
+    /// The rules, variant and deck come from the config and the command line.
+    Tune {
+        /// Where the best weights are written
+        #[arg(short, long, default_value = "heuristic.json")]
+        out: String,
+
+        /// The strategies the weights are tuned against, by name
+        #[arg(long, value_delimiter = ',', default_value = "naive,greedy")]
+        opponents: Vec<String>,
+
+        /// How many players every game seats, the rest being copies of the opponent
+        #[arg(long, default_value_t = 2)]
+        players: usize,
+
+        /// How many generations to evolve
+        #[arg(long, default_value_t = 10)]
+        generations: usize,
+
+        /// How many candidates every generation holds
+        #[arg(long, default_value_t = 12)]
+        population: usize,
+
+        /// How many of the best candidates carry over to the next generation unchanged
+        #[arg(long, default_value_t = 2)]
+        elite: usize,
+
+        /// The standard deviation of the noise added to every weight of a child
+        #[arg(long, default_value_t = 0.5)]
+        mutation: f64,
+
+        /// How many games every candidate plays against every opponent
+        #[arg(long = "batch", default_value_t = 200)]
+        batch: usize,
+
+        /// How many games the best weights play against every opponent at the end
+        #[arg(long, default_value_t = 2000)]
+        final_games: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RulesCommand {
+    /// Lists every preset
+    List,
+
+    /// Prints what a preset enables
+    Describe { preset: String },
 }
 
 #[derive(Deserialize)]
 struct PlayerConfig {
     name: String,
     strategy: String,
+
+    #[serde(default)]
+    params: BTreeMap<String, Value>,
+
+    /// The color chooser that declares the colors of the player's wild cards
+    #[serde(default)]
+    colors: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -42,6 +174,23 @@ struct GameConfig {
     verbose: bool,
 }
 
+#[derive(Deserialize)]
+struct DeckConfig {
+    colors: usize,
+    min_number: u8,
+    max_number: u8,
+}
+
+#[derive(Deserialize)]
+struct RulesConfig {
+    #[serde(default)]
+    preset: Option<String>,
+
+    /// Every other key overrides the rule of the same name
+    #[serde(flatten)]
+    overrides: BTreeMap<String, Value>,
+}
+
 #[derive(Deserialize, Default)]
 struct Config {
     players: Vec<PlayerConfig>,
@@ -54,6 +203,15 @@ struct Config {
 
     #[serde(default)]
     game_options: Option<GameConfig>,
+
+    #[serde(default)]
+    variant: Option<String>,
+
+    #[serde(default)]
+    deck: Option<DeckConfig>,
+
+    #[serde(default)]
+    rules: Option<RulesConfig>,
 }
 
 fn parse_config(filename: &String) -> Config {
@@ -66,15 +224,372 @@ fn parse_config(filename: &String) -> Config {
     }
 }
 
-fn main() {
+/// Spells JSON values the way `Rules::set` and the strategy registry parse them
+fn to_strings(values: BTreeMap<String, Value>) -> Vec<(String, String)> {
+    values
+        .into_iter()
+        .map(|(key, value)| match value {
+            Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect()
+}
+
+/// Runs a subcommand instead of playing the games of the config
+fn run_command(command: Command, args: &Args) {
+    match command {
+        Command::Rules {
+            command: RulesCommand::List,
+        } => {
+            for preset in Rules::PRESETS {
+                println!("{}", preset)
+            }
+        }
+        Command::Rules {
+            command: RulesCommand::Describe { preset },
+        } => {
+            let rules = Rules::preset(&preset).unwrap_or_else(|err| panic!("{}", err));
+            println!("{}", rules.describe())
+        }
+        Command::Strategies => println!("{}", StrategyRegistry::new().describe()),
+        Command::Train {
+            out,
+            resume,
+            episodes,
+            players,
+            eval_every,
+            eval_games,
+            alpha,
+            gamma,
+            epsilon,
+        } => {
+            let config = parse_config(&args.file);
+            let rates = LearningRates {
+                alpha,
+                gamma,
+                epsilon,
+            };
+            train(
+                &config,
+                args,
+                &out,
+                resume.as_deref(),
+                episodes,
+                players,
+                eval_every,
+                eval_games,
+                rates,
+            );
+        }
+        Command::Tune {
+            out,
+            opponents,
+            players,
+            generations,
+            population,
+            elite,
+            mutation,
+            batch,
+            final_games,
+        } => {
+            let config = parse_config(&args.file);
+            let tuner = GeneticTuner::new(population, elite, mutation, args.seed.unwrap_or(0));
+            tune(
+                &config,
+                args,
+                &out,
+                &opponents,
+                players,
+                generations,
+                tuner,
+                batch,
+                final_games,
+            );
+        }
+    }
+}
+
+/// Returns the variant, rules and deck of the config and the command line
+fn game_setup(config: &Config, args: &Args) -> GameSetup {
+    let mut setup = GameSetup::new();
+    if let Some(variant) = &config.variant {
+        setup = setup
+            .with_variant(variant)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    // The command line takes precedence over the config
+    let (preset, overrides) = match &config.rules {
+        Some(rules) => (rules.preset.clone(), to_strings(rules.overrides.clone())),
+        None => (None, Vec::new()),
+    };
+    let mut overrides = overrides;
+    for arg in args.overrides.iter() {
+        let (field, value) = arg
+            .split_once('=')
+            .unwrap_or_else(|| panic!("expected FIELD=VALUE, got {}", arg));
+        overrides.push((field.to_owned(), value.to_owned()));
+    }
+    if let Some(preset) = args.rules.clone().or(preset) {
+        setup = setup
+            .with_preset(&preset)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+    for (field, value) in overrides {
+        setup = setup
+            .with_rule(&field, &value)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+    if let Some(deck) = &config.deck {
+        let spec = DeckSpec::new(deck.colors, deck.min_number..=deck.max_number)
+            .unwrap_or_else(|err| panic!("bad deck in config: {}", err));
+        setup = setup.with_deck(spec);
+    }
+    setup
+}
+
+/// Returns a builder with the variant, rules and deck of the config and the command line
+fn game_master(config: &Config, args: &Args) -> GameMasterBuilder {
+    GameMasterBuilder::new().with_setup(game_setup(config, args))
+}
+
+/// Trains a Q-table by self-play, evaluating it against naive every `eval_every` games
+#[allow(clippy::too_many_arguments)]
+fn train(
+    config: &Config,
+    args: &Args,
+    out: &str,
+    resume: Option<&str>,
+    episodes: usize,
+    players: usize,
+    eval_every: usize,
+    eval_games: usize,
+    rates: LearningRates,
+) {
+    let table = match resume {
+        Some(file) => QTable::load(file).unwrap_or_else(|err| panic!("{}: {}", file, err)),
+        None => QTable::new(),
+    };
+    let seed = args.seed.unwrap_or(0);
+    let trainer = QTrainer::new(table, rates, seed);
+    let options = |games: usize| {
+        let opts = Options::new(
+            config.threads.unwrap_or(args.threads),
+            Duration::ZERO,
+            false,
+        )
+        .quiet();
+        match args.seed {
+            // Every batch deals games of its own
+            Some(seed) => opts.with_seed(seed.wrapping_add(games as u64)),
+            None => opts,
+        }
+    };
+
+    let start = Instant::now();
+    let mut done = 0;
+    while done < episodes {
+        let batch = eval_every.max(1).min(episodes - done);
+        let mut builder = game_master(config, args).with_options(options(done));
+        for seat in 0..players.max(2) {
+            builder = builder.add_player(format!("Learner {}", seat), trainer.learner());
+        }
+        builder.build().run(batch);
+        done += batch;
 
-    let args = Args::parse();
+        let mut builder = game_master(config, args)
+            .with_options(options(episodes + done))
+            .add_player("Policy".to_owned(), trainer.policy());
+        for seat in 1..players.max(2) {
+            builder = builder.add_player(format!("Naive {}", seat), StrategyFactory::naive());
+        }
+        let wins = builder.build().run(eval_games).wins();
+        let learned = trainer.table();
+        learned
+            .save(out)
+            .unwrap_or_else(|err| panic!("could not write {}: {}", out, err));
+        println!(
+            "{}/{} games, {} entries, won {}/{} against naive ({:.1}%), {:?}",
+            done,
+            episodes,
+            learned.len(),
+            wins[0],
+            eval_games,
+            100.0 * wins[0] as f64 / eval_games.max(1) as f64,
+            start.elapsed()
+        );
+    }
+    println!("Saved the policy to {}", out)
+}
+
+/// How a tuning run plays its games
+struct Tuning<'a> {
+    config: &'a Config,
+    args: &'a Args,
+    opponents: Vec<(String, StrategyFactory)>,
+    players: usize,
+}
+
+impl Tuning<'_> {
+    /// Returns the share of `games` games the heuristic with `weights` wins against every opponent,
+    /// with the games of a run dealt from `seed` onwards
+    fn win_rates(&self, weights: [f64; FEATURE_COUNT], games: usize, seed: u64) -> Vec<f64> {
+        let heuristic =
+            StrategyFactory::new("Heuristic", move || Box::new(Heuristic::new(weights)));
+        self.opponents
+            .iter()
+            .map(|(name, opponent)| {
+                let options = Options::new(
+                    self.config.threads.unwrap_or(self.args.threads),
+                    Duration::ZERO,
+                    false,
+                )
+                .quiet()
+                .with_seed(seed);
+                let mut builder = game_master(self.config, self.args)
+                    .with_options(options)
+                    .add_player("Heuristic".to_owned(), heuristic.clone());
+                for seat in 1..self.players.max(2) {
+                    builder = builder.add_player(format!("{} {}", name, seat), opponent.clone());
+                }
+                let wins = builder.build().run(games).wins();
+                wins[0] as f64 / games.max(1) as f64
+            })
+            .collect()
+    }
+}
+
+/// Spells weights the way the heuristic strategy takes them as parameters
+fn weight_params(weights: &[f64; FEATURE_COUNT]) -> Map<String, Value> {
+    FEATURES
+        .iter()
+        .zip(weights)
+        .map(|(feature, weight)| (feature.to_string(), json!(weight)))
+        .collect()
+}
+
+/// Tunes the heuristic against `opponents` and writes the best weights to `out`
+#[allow(clippy::too_many_arguments)]
+fn tune(
+    config: &Config,
+    args: &Args,
+    out: &str,
+    opponents: &[String],
+    players: usize,
+    generations: usize,
+    mut tuner: GeneticTuner,
+    batch: usize,
+    final_games: usize,
+) {
+    let registry = StrategyRegistry::new();
+    let opponents = opponents
+        .iter()
+        .map(|name| {
+            let strategy = registry
+                .build(name, &[])
+                .unwrap_or_else(|err| panic!("bad opponent: {}", err));
+            (name.clone(), strategy)
+        })
+        .collect();
+    let tuning = Tuning {
+        config,
+        args,
+        opponents,
+        players,
+    };
+    let seed = args.seed.unwrap_or(0);
+
+    let start = Instant::now();
+    let (best, _) = tuner.run(
+        DEFAULT_WEIGHTS,
+        generations,
+        |generation, weights| {
+            // Every candidate of a generation is dealt the same games
+            let rates = tuning.win_rates(
+                *weights,
+                batch,
+                seed.wrapping_add((generation as u64) << 32),
+            );
+            rates.iter().sum::<f64>() / rates.len() as f64
+        },
+        |generation, weights, fitness| {
+            println!(
+                "generation {}: won {:.1}% with {:?}, {:?}",
+                generation + 1,
+                100.0 * fitness,
+                weights,
+                start.elapsed()
+            )
+        },
+    );
+
+    // The final games are dealt apart from the ones tuned on
+    let final_seed = !seed;
+    let rates = tuning.win_rates(best, final_games, final_seed);
+    let baseline = tuning.win_rates(DEFAULT_WEIGHTS, final_games, final_seed);
+    let mut win_rates = Map::new();
+    for (((name, _), rate), baseline) in tuning.opponents.iter().zip(&rates).zip(&baseline) {
+        println!(
+            "against {}: won {:.1}% of {} games, {:.1}% with the default weights",
+            name,
+            100.0 * rate,
+            final_games,
+            100.0 * baseline
+        );
+        win_rates.insert(name.clone(), json!(rate));
+    }
+
+    // Written as a player config, so that it can be pasted into the players of a config
+    let player = json!({
+        "name": "Tuned",
+        "strategy": "heuristic",
+        "params": weight_params(&best),
+        "win_rates": win_rates,
+        "games": final_games,
+    });
+    let text = serde_json::to_string_pretty(&player).unwrap();
+    std::fs::write(out, text + "\n")
+        .unwrap_or_else(|err| panic!("could not write {}: {}", out, err));
+    println!("Saved the weights to {}", out)
+}
+
+fn main() {
+    env_logger::init();
+    let mut args = Args::parse();
+    if let Some(command) = args.command.take() {
+        run_command(command, &args);
+        return;
+    }
     let config = parse_config(&args.file);
 
-    let opts = Options::new(config.threads.unwrap_or(args.threads), false, true);
-    let mut builder = GameMasterBuilder::new();
-    for player in config.players {
-        builder = builder.add_player(player.name, Strategy::get(player.strategy));
+    let (pause, verbose) = match &config.game_options {
+        Some(game) => (game.pause_duration, game.verbose),
+        None => (args.pause, args.verbose),
+    };
+    let mut opts = Options::new(
+        config.threads.unwrap_or(args.threads),
+        Duration::from_millis(pause),
+        verbose,
+    );
+    if let Some(seed) = args.seed {
+        opts = opts.with_seed(seed);
+    }
+    if args.perfect_information {
+        opts = opts.perfect_information();
+    }
+    let registry = StrategyRegistry::new();
+    let mut builder = game_master(&config, &args);
+    for player in config.players.iter() {
+        let mut strategy = registry
+            .build(&player.strategy, &to_strings(player.params.clone()))
+            .unwrap_or_else(|err| panic!("bad strategy for {}: {}", player.name, err));
+        if let Some(colors) = &player.colors {
+            let chooser = registry
+                .color_chooser(colors)
+                .unwrap_or_else(|err| panic!("bad colors for {}: {}", player.name, err));
+            strategy = strategy.with_colors(chooser);
+        }
+        builder = builder.add_player(player.name.clone(), strategy);
     }
     let master = builder.with_options(opts).build();
 
@@ -86,4 +601,4 @@ fn main() {
 
     let elapsed = end - start;
     println!("Elapsed time: {:?}", elapsed)
-}
\ No newline at end of file
+}
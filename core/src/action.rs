@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::{card::Card, color::ColorSuite};
+
+/// A single move a player makes during their turn
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Action {
+    /// Plays cards from the hand onto the target at index `target`.
+    /// Uno and Crazy Eights play a single card onto target 0,
+    /// while Dos also allows two cards whose numbers sum to the target's number.
+    ///
+    /// `color` declares the color of any wild card played.
+    /// A wild card played without one is given a random color.
+    Play {
+        target: usize,
+        cards: Vec<Card>,
+        color: Option<ColorSuite>,
+    },
+
+    /// Lays a card from the hand down as a new target.
+    /// Dos rewards color matches this way.
+    Place {
+        card: Card,
+        color: Option<ColorSuite>,
+    },
+
+    /// Swaps hands with the player in `seat`.
+    /// The 7-0 rule asks for this after playing a 7.
+    Swap { seat: usize },
+
+    /// Draws from the deck, or takes a pending plus stack
+    Draw,
+
+    /// Ends the turn without doing anything else
+    Pass,
+}
+
+impl Action {
+    /// Constructs an action playing a single card onto the discard pile
+    pub fn play(card: Card) -> Self {
+        Self::Play {
+            target: 0,
+            cards: vec![card],
+            color: None,
+        }
+    }
+
+    /// Indicates whether this action moves cards out of the hand
+    pub fn is_play(&self) -> bool {
+        matches!(self, Self::Play { .. } | Self::Place { .. })
+    }
+
+    /// Returns the cards this action moves out of the hand
+    pub fn cards(&self) -> &[Card] {
+        match self {
+            Self::Play { cards, .. } => cards,
+            Self::Place { card, .. } => std::slice::from_ref(card),
+            _ => &[],
+        }
+    }
+
+    /// Indicates whether this is the same move as `other`, ignoring any declared color
+    pub fn same_move(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Play { target, cards, .. },
+                Self::Play {
+                    target: other_target,
+                    cards: other_cards,
+                    ..
+                },
+            ) => target == other_target && cards == other_cards,
+            (Self::Place { card, .. }, Self::Place { card: other, .. }) => card == other,
+            (Self::Swap { seat }, Self::Swap { seat: other }) => seat == other,
+            (Self::Draw, Self::Draw) | (Self::Pass, Self::Pass) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Play { cards, .. } => {
+                let cards: Vec<String> = cards.iter().map(|card| card.to_string()).collect();
+                write!(f, "played {}", cards.join(" + "))
+            }
+            Self::Place { card, .. } => write!(f, "added {} to the row", card),
+            Self::Swap { seat } => write!(f, "swapped hands with seat {}", seat),
+            Self::Draw => write!(f, "drew"),
+            Self::Pass => write!(f, "passed"),
+        }
+    }
+}
@@ -1,34 +1,503 @@
+use std::collections::HashMap;
+
 use rand::{seq::SliceRandom, thread_rng};
 
 use crate::{
+    action::Action,
     card::Card,
-    color::{Color, ColorSuite},
+    card_counter::CardCounter,
+    color::ColorSuite,
+    event::GameEvent,
+    face::Face,
     hand::Hand,
-    player::PlayResult,
-    rules::Rules,
+    hand_analysis::{CardHistogram, HandAnalysis},
+    opponent_model::OpponentModel,
+    strategy::Strategy,
+    view::PlayerView,
 };
 
-fn random(hand: &mut Hand, candidates: Vec<usize>) -> PlayResult {
-    // This unwrap is safe right now because we
-    let i = *candidates.choose(&mut thread_rng()).unwrap();
-    let mut played = hand.remove(i);
-    match played.color {
-        Color::Wild(None) => played.assign_color(ColorSuite::random()),
-        _ => {}
+/// The stupidest algorithm.
+/// Plays a random card whenever it can, leaving wild colors up to chance.
+pub struct Naive;
+
+impl Strategy for Naive {
+    fn name(&self) -> &str {
+        "Naive"
+    }
+
+    fn choose(&mut self, moves: &[Action], _view: &PlayerView) -> Action {
+        let plays: Vec<&Action> = moves.iter().filter(|action| action.is_play()).collect();
+        match plays.choose(&mut thread_rng()) {
+            Some(&action) => action.clone(),
+            // With nothing to play, the only moves left are drawing or passing
+            None => moves[0].clone(),
+        }
+    }
+}
+
+/// Returns the color `hand` holds the most of, or a random color if it holds no colored cards
+fn most_held(hand: &Hand, view: &PlayerView) -> ColorSuite {
+    match CardHistogram::new(hand).max() {
+        Some((color, _)) => color,
+        None => ColorSuite::random(view.colors()),
     }
-    PlayResult::new(Some(played), hand.is_empty())
 }
 
-/// The stupidest algorithm
-pub fn naive(card: &Card, hand: &mut Hand, rules: &Rules, plus_stack: bool) -> PlayResult {
-    let candidates = if plus_stack {
-        hand.all_plus_stackable(card, &rules.plus_stacking)
-    } else {
-        hand.all_stackable_indices(card)
+/// Declares the color the rest of the hand holds the most of for any wild card `action` plays
+fn declare_most_held(mut action: Action, view: &PlayerView) -> Action {
+    if !action.cards().iter().any(Card::is_wild) {
+        return action;
+    }
+    let mut rest = view.hand().clone();
+    for card in action.cards() {
+        rest.remove_card(card);
+    }
+    if let Action::Play { color, .. } | Action::Place { color, .. } = &mut action {
+        *color = Some(most_held(&rest, view));
+    }
+    action
+}
+
+/// Picks a move when there is nothing to play:
+/// swaps with the smallest hand if it may, otherwise draws or passes
+fn without_plays(moves: &[Action], view: &PlayerView) -> Action {
+    moves
+        .iter()
+        .min_by_key(|action| match action {
+            Action::Swap { seat } => view.hand_sizes()[*seat],
+            _ => usize::MAX,
+        })
+        .unwrap()
+        .clone()
+}
+
+/// Plays the card that leaves the most of its hand playable on top of it,
+/// holding wild cards back while the hand is large
+/// and shedding the highest points among equally flexible cards,
+/// and declares the color it holds the most of.
+pub struct Greedy {
+    /// Hand size at or below which wild cards are no longer held back
+    hold_wilds_until: usize,
+}
+
+impl Greedy {
+    pub fn new(hold_wilds_until: usize) -> Self {
+        Self { hold_wilds_until }
+    }
+
+    /// Rates playing `cards`, higher is better.
+    /// Emptier hands come first, then keeping wilds, then the cards left playable,
+    /// then shedding the most points.
+    fn score(&self, cards: &[Card], view: &PlayerView) -> (usize, bool, usize, usize) {
+        let mut rest = view.hand().clone();
+        for card in cards {
+            rest.remove_card(card);
+        }
+        let mut top = *cards.last().unwrap();
+        if top.color_suite().is_none() {
+            top.assign_color(most_held(&rest, view));
+        }
+        let holding = view.hand().len() > self.hold_wilds_until;
+        let keeps_wilds = !holding || !cards.iter().any(Card::is_wild);
+        (
+            cards.len(),
+            keeps_wilds,
+            HandAnalysis::new(&rest).playable_on(&top),
+            cards.iter().map(Card::points).sum(),
+        )
+    }
+}
+
+impl Strategy for Greedy {
+    fn name(&self) -> &str {
+        "Greedy"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let best = moves
+            .iter()
+            .filter(|action| action.is_play())
+            .max_by_key(|action| self.score(action.cards(), view));
+        match best {
+            Some(action) => declare_most_held(action.clone(), view),
+            None => without_plays(moves, view),
+        }
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        Some(most_held(view.hand(), view))
+    }
+}
+
+/// Saves wild cards for emergencies and sheds its most valuable cards first.
+/// Wild cards are only played when nothing else can be,
+/// when a plus stack is pending or when an opponent is close to going out.
+/// With strategic drawing, it draws instead of playing a wild card
+/// that nothing forces it to play, keeping the wild card for later.
+pub struct Defensive {
+    /// Opponent hand size at or below which wild cards are played freely
+    threat: usize,
+
+    /// Whether to draw rather than play a wild card outside of an emergency
+    draw_over_wilds: bool,
+}
+
+impl Defensive {
+    pub fn new(threat: usize, draw_over_wilds: bool) -> Self {
+        Self {
+            threat,
+            draw_over_wilds,
+        }
+    }
+
+    /// Indicates whether wild cards should be played right away
+    fn emergency(&self, view: &PlayerView) -> bool {
+        view.stack_count() > 0
+            || view
+                .hand_sizes()
+                .iter()
+                .enumerate()
+                .any(|(seat, &size)| seat != view.seat() && size <= self.threat)
+    }
+}
+
+impl Strategy for Defensive {
+    fn name(&self) -> &str {
+        "Defensive"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let plays: Vec<&Action> = moves.iter().filter(|action| action.is_play()).collect();
+        let safe: Vec<&Action> = plays
+            .iter()
+            .filter(|action| !action.cards().iter().any(Card::is_wild))
+            .copied()
+            .collect();
+        let draw = moves.iter().find(|&action| *action == Action::Draw);
+
+        let candidates = match (safe.is_empty(), self.emergency(view), draw) {
+            (false, _, _) => safe,
+            // Only wild cards can be played, and drawing keeps them for an emergency
+            (true, false, Some(draw)) if self.draw_over_wilds => return draw.clone(),
+            (true, _, _) => plays,
+        };
+        let best = candidates.into_iter().max_by_key(|action| {
+            let points: usize = action.cards().iter().map(Card::points).sum();
+            (points, action.cards().len())
+        });
+        match best {
+            Some(action) => declare_most_held(action.clone(), view),
+            None => without_plays(moves, view),
+        }
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        Some(most_held(view.hand(), view))
+    }
+}
+
+/// Steers its attacks at the leader, the opponent with the fewest cards.
+/// Skip, +2 and +4 are saved for when the leader plays next,
+/// Reverse is used to turn play away from the leader,
+/// and wild cards are declared as a color the leader failed to match.
+#[derive(Default)]
+pub struct Aggressive {
+    opponents: OpponentModel,
+}
+
+impl Aggressive {
+    /// Returns the opponent with the fewest cards
+    fn leader(view: &PlayerView) -> usize {
+        (0..view.players())
+            .filter(|&seat| seat != view.seat())
+            .min_by_key(|&seat| view.hand_sizes()[seat])
+            .unwrap()
+    }
+
+    /// Indicates whether `card` hurts whoever plays next
+    fn attacks(card: &Card) -> bool {
+        matches!(card.face, Face::Skip | Face::PlusTwo | Face::PlusFour)
+    }
+
+    /// Rates a play, higher is better
+    fn score(action: &Action, leader_next: bool, players: usize) -> (u8, usize) {
+        let cards = action.cards();
+        let points = cards.iter().map(Card::points).sum();
+        let kind = if cards.iter().any(Self::attacks) {
+            if leader_next {
+                3
+            } else {
+                0
+            }
+        } else if cards.iter().any(|card| card.face == Face::Reverse) && players > 2 {
+            // Reversing turns play away from the next player and towards the previous one
+            if leader_next {
+                2
+            } else {
+                0
+            }
+        } else {
+            1
+        };
+        (kind, points)
+    }
+}
+
+impl Strategy for Aggressive {
+    fn name(&self) -> &str {
+        "Aggressive"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let leader = Self::leader(view);
+        let leader_next = view.next_seat() == leader;
+        let best = moves
+            .iter()
+            .filter(|action| action.is_play())
+            .max_by_key(|action| Self::score(action, leader_next, view.players()));
+        let Some(action) = best else {
+            // Take the leader's hand whenever the 7-0 rule allows it
+            let swap = Action::Swap { seat: leader };
+            if moves.contains(&swap) {
+                return swap;
+            }
+            return without_plays(moves, view);
+        };
+
+        let mut action = declare_most_held(action.clone(), view);
+        // Prefer a color the leader lacks, as long as the hand can follow it up
+        let histogram = CardHistogram::new(view.hand());
+        let lacking = self.opponents.lacking(leader, view.colors());
+        let target = lacking
+            .into_iter()
+            .filter(|&color| histogram.color(color) > 0)
+            .max_by_key(|&color| histogram.color(color));
+        if let Some(color) = target {
+            match &mut action {
+                Action::Play {
+                    color: declared, ..
+                }
+                | Action::Place {
+                    color: declared, ..
+                } if declared.is_some() => *declared = Some(color),
+                _ => {}
+            }
+        }
+        action
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        self.opponents.on_event(event)
+    }
+}
+
+/// Counts cards to keep the next player from following up.
+/// Every play, and every color for a wild card, is rated by the chance
+/// that the player who moves next holds a card that can go on top of it,
+/// assuming their hand is a random draw from the cards it has not seen.
+/// Run with debug logging to see its estimates.
+#[derive(Default)]
+pub struct Counting {
+    counter: CardCounter,
+}
+
+impl Counting {
+    /// Returns the seat that moves after `card` is played
+    pub(crate) fn follower(card: &Card, view: &PlayerView) -> usize {
+        let players = view.players();
+        let step = (view.next_seat() + players - view.seat()) % players;
+        match card.face {
+            Face::Skip => (view.seat() + 2 * step) % players,
+            Face::Reverse if players > 2 => (view.seat() + players - step) % players,
+            _ => view.next_seat(),
+        }
+    }
+
+    /// Returns the chance that the follower can play on `top`.
+    /// A follower in the player's own seat never counts as a threat.
+    fn chance(unseen: &HashMap<Card, usize>, top: &Card, view: &PlayerView) -> f64 {
+        let follower = Self::follower(top, view);
+        if follower == view.seat() {
+            return 0.0;
+        }
+        CardCounter::follow_chance(unseen, top, view.hand_sizes()[follower])
+    }
+
+    /// Returns the color for a wild `top` that the follower is least likely to match,
+    /// along with that chance. Ties go to the color the rest of the hand holds more of.
+    fn best_color(
+        unseen: &HashMap<Card, usize>,
+        top: &Card,
+        rest: &Hand,
+        view: &PlayerView,
+    ) -> (ColorSuite, f64) {
+        let histogram = CardHistogram::new(rest);
+        view.colors()
+            .iter()
+            .map(|&color| {
+                let mut top = *top;
+                top.assign_color(color);
+                (color, Self::chance(unseen, &top, view))
+            })
+            .min_by(|(a, chance_a), (b, chance_b)| {
+                chance_a
+                    .total_cmp(chance_b)
+                    .then(histogram.color(*b).cmp(&histogram.color(*a)))
+            })
+            .unwrap_or_else(|| panic!("the game has no colors"))
+    }
+
+    /// Declares the best color for any wild card `action` plays
+    /// and returns the chance the follower can play on it
+    fn rate(
+        &self,
+        mut action: Action,
+        unseen: &HashMap<Card, usize>,
+        view: &PlayerView,
+    ) -> (Action, f64) {
+        let mut rest = view.hand().clone();
+        for card in action.cards() {
+            rest.remove_card(card);
+        }
+        let top = *action.cards().last().unwrap();
+        let chance = if top.is_wild() {
+            let (best, chance) = Self::best_color(unseen, &top, &rest, view);
+            if let Action::Play { color, .. } | Action::Place { color, .. } = &mut action {
+                *color = Some(best);
+            }
+            chance
+        } else {
+            Self::chance(unseen, &top, view)
+        };
+        log::debug!(
+            "seat {} estimates a {:.1}% chance of being followed if it {}",
+            view.seat(),
+            chance * 100.0,
+            action
+        );
+        (action, chance)
+    }
+}
+
+impl Strategy for Counting {
+    fn name(&self) -> &str {
+        "Counting"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        self.counter.sync(view);
+        let unseen = self.counter.unseen(view.hand());
+        let best = moves
+            .iter()
+            .filter(|action| action.is_play())
+            .map(|action| self.rate(action.clone(), &unseen, view))
+            .min_by(|(a, chance_a), (b, chance_b)| {
+                let points =
+                    |action: &Action| action.cards().iter().map(Card::points).sum::<usize>();
+                chance_a.total_cmp(chance_b).then(points(b).cmp(&points(a)))
+            });
+        match best {
+            Some((action, _)) => action,
+            None => without_plays(moves, view),
+        }
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        // The flipped card's event has not been published yet, so count from scratch
+        let mut counter = CardCounter::new();
+        counter.sync(view);
+        let unseen = counter.unseen(view.hand());
+        let (color, chance) = Self::best_color(&unseen, view.top(), view.hand(), view);
+        log::debug!(
+            "seat {} declares {} with a {:.1}% chance of being followed",
+            view.seat(),
+            color,
+            chance * 100.0
+        );
+        Some(color)
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        if let GameEvent::Reshuffled { cards } = event {
+            log::debug!("{} cards were reshuffled, recounting the table", cards);
+        }
+        self.counter.on_event(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::GameState,
+        variants::{Classic, Ruleset},
     };
-    if candidates.len() == 0 {
-        PlayResult::new(None, false)
-    } else {
-        random(hand, candidates)
+
+    fn red(n: u8) -> Card {
+        Card::new_colored(Face::Number(n), ColorSuite::Red)
+    }
+
+    fn blue(n: u8) -> Card {
+        Card::new_colored(Face::Number(n), ColorSuite::Blue)
+    }
+
+    /// A classic game where seat 0 is in turn with `hands[0]`
+    /// and `top` on the discard pile
+    fn table(hands: &[Vec<Card>], top: Card) -> GameState {
+        let rules = Classic.default_rules();
+        let mut state = GameState::new(
+            hands.len(),
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(5),
+        );
+        for (hand, cards) in state.hands.iter_mut().zip(hands) {
+            hand.add_many(cards.clone());
+        }
+        state.table.add_target(top);
+        state.order.next();
+        state
+    }
+
+    fn choose(strategy: &mut dyn Strategy, state: &GameState) -> Action {
+        let moves = Classic.legal_moves(state, 0);
+        strategy.choose(&moves, &PlayerView::new(&Classic, state, 0))
+    }
+
+    #[test]
+    fn greedy_sheds_the_highest_points_among_equally_flexible_cards() {
+        let opponent: Vec<Card> = (1..=7).map(blue).collect();
+        let hands = [vec![red(2), red(9), blue(5)], opponent];
+        let state = table(&hands, red(7));
+        assert_eq!(choose(&mut Greedy::new(2), &state), Action::play(red(9)));
+    }
+
+    #[test]
+    fn defensive_plays_a_wild_card_when_nothing_else_matches() {
+        let wild = Card::new_wild(Face::Wild);
+        let opponent: Vec<Card> = (1..=7).map(blue).collect();
+        let state = table(&[vec![wild, blue(3)], opponent], red(7));
+
+        let action = choose(&mut Defensive::new(2, false), &state);
+        assert!(matches!(
+            action,
+            Action::Play { cards, color: Some(ColorSuite::Blue), .. } if cards == [wild]
+        ));
+        assert_eq!(choose(&mut Defensive::new(2, true), &state), Action::Draw);
+    }
+
+    #[test]
+    fn aggressive_saves_action_cards_for_a_short_handed_next_player() {
+        let skip = Card::new_colored(Face::Skip, ColorSuite::Red);
+        let hand = vec![skip, red(9)];
+        let many: Vec<Card> = (1..=5).map(blue).collect();
+
+        let state = table(&[hand.clone(), vec![blue(1)], many.clone()], red(3));
+        let mut aggressive = Aggressive::default();
+        assert_eq!(choose(&mut aggressive, &state), Action::play(skip));
+
+        let state = table(&[hand, many, vec![blue(1)]], red(3));
+        assert_eq!(choose(&mut aggressive, &state), Action::play(red(9)));
     }
 }
@@ -1,14 +1,11 @@
-use core::panic;
 use std::fmt;
 
-use colored::Colorize;
-
 use crate::color::{Color, ColorSuite};
 use crate::face::Face;
 use crate::rules::PlusStacking;
 
 /// Represents an Uno Card
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash, Debug)]
 pub struct Card {
     pub face: Face,
     pub color: Color,
@@ -67,22 +64,42 @@ impl Card {
         }
     }
 
+    /// Returns the points this card is worth to the winner under official scoring.
+    /// Crazy Eights scores its face cards at 10 and its eights at 50.
+    pub fn points(&self) -> usize {
+        match self.face {
+            Face::Number(n) => n as usize,
+            Face::Rank(_) if self.is_wild() => 50,
+            Face::Rank(n) => n.min(10) as usize,
+            Face::Skip | Face::Reverse | Face::PlusTwo => 20,
+            Face::Wild | Face::PlusFour => 50,
+        }
+    }
+
     /// Indicates whether this card is a simple numeric card.
+    /// Ranks other than the wild eights count as numeric.
     pub fn is_num(&self) -> bool {
         match self.face {
-            Face::Zero
-            | Face::One
-            | Face::Two
-            | Face::Three
-            | Face::Four
-            | Face::Six
-            | Face::Seven
-            | Face::Eight
-            | Face::Nine => true,
+            Face::Number(_) => true,
+            Face::Rank(_) => !self.is_wild(),
             _ => false,
         }
     }
 
+    /// Returns the color this card counts as,
+    /// or `None` if it is a wild card without a declared color.
+    pub fn color_suite(&self) -> Option<ColorSuite> {
+        match self.color {
+            Color::Standard(color) | Color::Wild(Some(color)) => Some(color),
+            Color::Wild(None) => None,
+        }
+    }
+
+    /// Indicates whether this card was dealt as a wild card, whether or not a color was declared.
+    pub fn is_wild(&self) -> bool {
+        matches!(self.color, Color::Wild(_))
+    }
+
     pub fn is_special(&self) -> bool {
         !self.is_num()
     }
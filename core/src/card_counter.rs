@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use crate::{card::Card, event::GameEvent, hand::Hand, view::PlayerView};
+
+/// Returns `card` as it was dealt, dropping any color declared for a wild card
+pub(crate) fn as_dealt(card: &Card) -> Card {
+    if card.is_wild() {
+        Card::new_wild(card.face)
+    } else {
+        *card
+    }
+}
+
+/// Keeps an exact tally of the cards a player has not seen,
+/// which are either in the deck or in an opponent's hand
+#[derive(Default)]
+pub struct CardCounter {
+    /// How many copies of each card the game is played with
+    composition: HashMap<Card, usize>,
+
+    /// The cards face up on the table since the last reshuffle
+    table: HashMap<Card, usize>,
+
+    /// Whether the table tally is up to date.
+    /// It has to be counted again at the start of the game and after every reshuffle.
+    counted: bool,
+}
+
+impl CardCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Brings the tally up to date with what the player can see.
+    /// Should be called at the start of every decision.
+    pub fn sync(&mut self, view: &PlayerView) {
+        if self.composition.is_empty() {
+            for card in view.composition() {
+                *self.composition.entry(as_dealt(card)).or_insert(0) += 1;
+            }
+        }
+        if !self.counted {
+            self.table.clear();
+            for card in view.face_up() {
+                *self.table.entry(as_dealt(&card)).or_insert(0) += 1;
+            }
+            self.counted = true;
+        }
+    }
+
+    pub fn on_event(&mut self, event: &GameEvent) {
+        let cards = match event {
+            GameEvent::Flipped { card } | GameEvent::Placed { card, .. } => vec![*card],
+            GameEvent::Played { cards, .. } => cards.clone(),
+            // The reshuffle event doesn't say which cards stayed on the table,
+            // so they are counted again at the next decision
+            GameEvent::Reshuffled { .. } => {
+                self.counted = false;
+                return;
+            }
+            _ => return,
+        };
+        if self.counted {
+            for card in cards {
+                *self.table.entry(as_dealt(&card)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Returns how many copies of each card the player has not seen
+    pub fn unseen(&self, hand: &Hand) -> HashMap<Card, usize> {
+        let mut unseen = self.composition.clone();
+        for (card, count) in self.table.iter() {
+            if let Some(left) = unseen.get_mut(card) {
+                *left = left.saturating_sub(*count);
+            }
+        }
+        for card in hand {
+            if let Some(left) = unseen.get_mut(&as_dealt(card)) {
+                *left = left.saturating_sub(1);
+            }
+        }
+        unseen.retain(|_, count| *count > 0);
+        unseen
+    }
+
+    /// Returns the chance that a hand of `hand_size` cards dealt from `unseen`
+    /// holds at least one card that can be played on `top`
+    pub fn follow_chance(unseen: &HashMap<Card, usize>, top: &Card, hand_size: usize) -> f64 {
+        let total: usize = unseen.values().sum();
+        let matching: usize = unseen
+            .iter()
+            .filter(|(card, _)| card.stacks_on(top))
+            .map(|(_, count)| count)
+            .sum();
+        chance_of_any(total, matching, hand_size)
+    }
+}
+
+/// Returns the chance that `draws` cards taken from `total` cards
+/// include at least one of the `matching` ones
+pub(crate) fn chance_of_any(total: usize, matching: usize, draws: usize) -> f64 {
+    // Every draw misses the matching cards with hypergeometric odds
+    let mut miss = 1.0;
+    for i in 0..draws.min(total) {
+        let left = (total - i) as f64;
+        let misses = total.saturating_sub(matching + i) as f64;
+        miss *= misses / left;
+    }
+    1.0 - miss
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::ColorSuite,
+        face::Face,
+        state::GameState,
+        variants::{Classic, Ruleset},
+    };
+
+    fn red(n: u8) -> Card {
+        Card::new_colored(Face::Number(n), ColorSuite::Red)
+    }
+
+    #[test]
+    fn counts_the_table_again_after_a_reshuffle() {
+        let rules = Classic.default_rules();
+        let mut state = GameState::new(
+            2,
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(2),
+        );
+        state.hands[0].add_card(red(5));
+        state.dealer.replace_cards(vec![red(1)]);
+        state.table.add_target(red(7));
+
+        let mut counter = CardCounter::new();
+        counter.sync(&PlayerView::new(&Classic, &state, 0));
+        let mut wild = Card::new_wild(Face::Wild);
+        wild.assign_color(ColorSuite::Red);
+        for card in [wild, red(3)] {
+            state.table.play(0, card);
+            counter.on_event(&GameEvent::Played {
+                seat: 1,
+                target: 0,
+                cards: vec![card],
+            });
+        }
+        let unseen = counter.unseen(&state.hands[0]);
+        assert_eq!(unseen[&red(7)], 1);
+        assert_eq!(unseen[&Card::new_wild(Face::Wild)], 3);
+
+        // The second card comes from the seven and the wild card shuffled back into the deck
+        assert_eq!(state.draw(1, 2), 2);
+        for event in state.take_events() {
+            counter.on_event(&event);
+        }
+        let view = PlayerView::new(&Classic, &state, 0);
+        counter.sync(&view);
+        let unseen = counter.unseen(view.hand());
+        assert_eq!(unseen[&red(7)], 2);
+        assert_eq!(unseen[&Card::new_wild(Face::Wild)], 4);
+        assert_eq!(unseen[&red(3)], 1);
+        assert_eq!(unseen[&red(5)], 1);
+
+        let mut fresh = CardCounter::new();
+        fresh.sync(&view);
+        assert_eq!(unseen, fresh.unseen(view.hand()));
+    }
+}
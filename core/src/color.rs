@@ -4,38 +4,99 @@ use rand::{rngs::SmallRng, thread_rng, Rng, SeedableRng};
 
 use colored::{ColoredString, Colorize};
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash)]
 pub enum ColorSuite {
     Red,
     Yellow,
     Green,
     Blue,
+    Purple,
+    Orange,
+    Teal,
+    Pink,
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
 }
 
 impl ColorSuite {
-    /// Returns an array containing all colors except Undecided.
-    pub fn all() -> [Self; 4] {
-        [Self::Red, Self::Blue, Self::Green, Self::Yellow]
+    /// The most colors a deck can be built with.
+    pub const MAX_COLORS: usize = 8;
+
+    /// Every color in the order decks pick them up.
+    /// The first four are the colors of a standard Uno deck.
+    const PALETTE: [Self; Self::MAX_COLORS] = [
+        Self::Red,
+        Self::Blue,
+        Self::Green,
+        Self::Yellow,
+        Self::Purple,
+        Self::Orange,
+        Self::Teal,
+        Self::Pink,
+    ];
+
+    /// Returns the first `n` colors of the palette.
+    /// `n` is clamped to `MAX_COLORS`.
+    pub fn palette(n: usize) -> &'static [Self] {
+        &Self::PALETTE[..n.min(Self::MAX_COLORS)]
+    }
+
+    /// Returns the four colors of a standard Uno deck.
+    pub fn standard() -> &'static [Self] {
+        Self::palette(4)
+    }
+
+    /// Returns the four suits of a standard 52 card deck.
+    pub fn suits() -> &'static [Self] {
+        &[Self::Spades, Self::Hearts, Self::Diamonds, Self::Clubs]
+    }
+
+    /// Returns the plain name of this color, in lowercase
+    pub fn name(&self) -> String {
+        format!("{:?}", self).to_lowercase()
+    }
+
+    /// Returns the color called `name`, ignoring case
+    pub fn named(name: &str) -> Option<Self> {
+        Self::PALETTE
+            .iter()
+            .chain(Self::suits())
+            .copied()
+            .find(|color| color.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns a random color out of `colors`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `colors` is empty.
+    pub fn random(colors: &[Self]) -> Self {
+        Self::random_with(colors, &mut SmallRng::from_rng(thread_rng()).unwrap())
     }
 
-    /// Returns a random color excluding Undecided.
-    pub fn random() -> Self {
-        let colors = Self::all();
-        let i = SmallRng::from_rng(thread_rng())
-            .unwrap()
-            .gen_range(0..colors.len());
-        colors[i]
+    /// Returns a random color out of `colors`, drawn from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `colors` is empty.
+    pub fn random_with<R: Rng + ?Sized>(colors: &[Self], rng: &mut R) -> Self {
+        colors[rng.gen_range(0..colors.len())]
     }
 
-    fn colorize<T>(&self) -> fn(T) -> ColoredString
-    where
-        T: Sized + Colorize,
-    {
+    fn paint(&self, s: &str) -> ColoredString {
         match self {
-            Self::Red => Colorize::red,
-            Self::Yellow => Colorize::yellow,
-            Self::Green => Colorize::green,
-            Self::Blue => Colorize::blue,
+            Self::Red => s.red(),
+            Self::Yellow => s.yellow(),
+            Self::Green => s.green(),
+            Self::Blue => s.blue(),
+            Self::Purple => s.magenta(),
+            Self::Orange => s.truecolor(255, 140, 0),
+            Self::Teal => s.cyan(),
+            Self::Pink => s.truecolor(255, 105, 180),
+            Self::Hearts | Self::Diamonds => s.red(),
+            Self::Spades | Self::Clubs => s.normal(),
         }
     }
 }
@@ -47,13 +108,21 @@ impl fmt::Display for ColorSuite {
             Self::Blue => "Blue",
             Self::Green => "Green",
             Self::Yellow => "Yellow",
+            Self::Purple => "Purple",
+            Self::Orange => "Orange",
+            Self::Teal => "Teal",
+            Self::Pink => "Pink",
+            Self::Spades => "♠",
+            Self::Hearts => "♥",
+            Self::Diamonds => "♦",
+            Self::Clubs => "♣",
         };
-        let colored = self.colorize()(str);
+        let colored = self.paint(str);
         write!(f, "{colored}")
     }
 }
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash)]
 pub enum Color {
     Standard(ColorSuite),
     Wild(Option<ColorSuite>),
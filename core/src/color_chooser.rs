@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use crate::{
+    action::Action, color::ColorSuite, event::GameEvent, opponent_model::OpponentModel,
+    rules::Rules, strategy::Strategy, view::PlayerView,
+};
+
+/// Decides which color to declare for a wild card.
+/// Any strategy can be paired with any chooser through `StrategyFactory::with_colors`.
+pub trait ColorChooser: Send {
+    fn name(&self) -> &str;
+
+    /// Picks one of `view.colors()`
+    fn choose_color(&mut self, view: &PlayerView) -> ColorSuite;
+
+    /// Called for every event of the game
+    fn on_event(&mut self, _event: &GameEvent) {}
+}
+
+/// Builds a fresh `ColorChooser` for every game
+pub type ColorChooserFactory = fn() -> Box<dyn ColorChooser>;
+
+/// The built in color choosers with their descriptions, by name
+pub const COLOR_CHOOSERS: [(&str, &str, ColorChooserFactory); 4] = [
+    ("random", "Declares a random color", || {
+        Box::new(RandomColor)
+    }),
+    (
+        "most-frequent",
+        "Declares the color the player holds the most of",
+        || Box::new(MostFrequent),
+    ),
+    (
+        "next-lacks",
+        "Declares a color the next player was seen drawing on",
+        || Box::new(NextLacks::default()),
+    ),
+    (
+        "least-seen",
+        "Declares the color with the fewest cards seen so far",
+        || Box::new(LeastSeen),
+    ),
+];
+
+/// Returns the built in color chooser registered under `name`, ignoring case
+pub fn by_name(name: &str) -> Option<ColorChooserFactory> {
+    COLOR_CHOOSERS
+        .iter()
+        .find(|(chooser, _, _)| chooser.eq_ignore_ascii_case(name))
+        .map(|&(_, _, build)| build)
+}
+
+/// Counts the colored cards in the player's hand for every color of the game
+fn hand_counts(view: &PlayerView) -> HashMap<ColorSuite, usize> {
+    let mut counts: HashMap<ColorSuite, usize> =
+        view.colors().iter().map(|&color| (color, 0)).collect();
+    for card in view.hand().into_iter().filter(|card| !card.is_wild()) {
+        if let Some(count) = card.color_suite().and_then(|color| counts.get_mut(&color)) {
+            *count += 1;
+        }
+    }
+    counts
+}
+
+/// Returns the color out of `candidates` the player holds the most of.
+/// Ties go to the color that comes first.
+fn most_held(view: &PlayerView, candidates: &[ColorSuite]) -> Option<ColorSuite> {
+    let counts = hand_counts(view);
+    candidates
+        .iter()
+        .rev()
+        .max_by_key(|color| counts.get(color).copied().unwrap_or(0))
+        .copied()
+}
+
+/// Leaves every color up to chance
+pub struct RandomColor;
+
+impl ColorChooser for RandomColor {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> ColorSuite {
+        ColorSuite::random(view.colors())
+    }
+}
+
+/// Declares the color the player holds the most of, so they can keep playing it
+pub struct MostFrequent;
+
+impl ColorChooser for MostFrequent {
+    fn name(&self) -> &str {
+        "most-frequent"
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> ColorSuite {
+        most_held(view, view.colors()).unwrap_or_else(|| ColorSuite::random(view.colors()))
+    }
+}
+
+/// Declares a color the next player is believed to lack
+#[derive(Default)]
+pub struct NextLacks {
+    opponents: OpponentModel,
+}
+
+impl ColorChooser for NextLacks {
+    fn name(&self) -> &str {
+        "next-lacks"
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> ColorSuite {
+        let lacking = self.opponents.lacking(view.next_seat(), view.colors());
+        most_held(view, &lacking)
+            .or_else(|| most_held(view, view.colors()))
+            .unwrap_or_else(|| ColorSuite::random(view.colors()))
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        self.opponents.on_event(event)
+    }
+}
+
+/// Declares the color with the fewest cards seen on the table and in the player's hand
+pub struct LeastSeen;
+
+impl ColorChooser for LeastSeen {
+    fn name(&self) -> &str {
+        "least-seen"
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> ColorSuite {
+        let mut counts = hand_counts(view);
+        for card in view.history().iter().filter(|card| !card.is_wild()) {
+            if let Some(count) = card.color_suite().and_then(|color| counts.get_mut(&color)) {
+                *count += 1;
+            }
+        }
+        *view
+            .colors()
+            .iter()
+            .min_by_key(|color| counts[color])
+            .unwrap_or_else(|| panic!("the game has no colors"))
+    }
+}
+
+/// Pairs a strategy that picks cards with a chooser that picks the colors of its wild cards
+pub struct WithColors {
+    name: String,
+    inner: Box<dyn Strategy>,
+    chooser: Box<dyn ColorChooser>,
+}
+
+impl WithColors {
+    pub fn new(inner: Box<dyn Strategy>, chooser: Box<dyn ColorChooser>) -> Self {
+        Self {
+            name: format!("{} + {}", inner.name(), chooser.name()),
+            inner,
+            chooser,
+        }
+    }
+}
+
+impl Strategy for WithColors {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn on_game_start(&mut self, seat: usize, rules: &Rules) {
+        self.inner.on_game_start(seat, rules)
+    }
+
+    /// Lets the inner strategy pick a move, then declares the color of any wild card it plays,
+    /// replacing whatever color the inner strategy declared
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let mut action = self.inner.choose(moves, view);
+        if action.cards().iter().any(|card| card.is_wild()) {
+            if let Action::Play { color, .. } | Action::Place { color, .. } = &mut action {
+                *color = Some(self.chooser.choose_color(view));
+            }
+        }
+        action
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        Some(self.chooser.choose_color(view))
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        self.inner.on_event(event);
+        self.chooser.on_event(event);
+    }
+
+    fn on_game_end(&mut self, won: bool) {
+        self.inner.on_game_end(won)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        card::Card,
+        face::Face,
+        state::GameState,
+        variants::{Classic, Ruleset},
+    };
+
+    /// Always plays a wild card declared red
+    struct DeclaresRed;
+
+    impl Strategy for DeclaresRed {
+        fn name(&self) -> &str {
+            "DeclaresRed"
+        }
+
+        fn choose(&mut self, _moves: &[Action], _view: &PlayerView) -> Action {
+            Action::Play {
+                target: 0,
+                cards: vec![Card::new_wild(Face::Wild)],
+                color: Some(ColorSuite::Red),
+            }
+        }
+    }
+
+    struct AlwaysBlue;
+
+    impl ColorChooser for AlwaysBlue {
+        fn name(&self) -> &str {
+            "always-blue"
+        }
+
+        fn choose_color(&mut self, _view: &PlayerView) -> ColorSuite {
+            ColorSuite::Blue
+        }
+    }
+
+    #[test]
+    fn the_chooser_declares_the_color_even_when_the_strategy_did() {
+        let rules = Classic.default_rules();
+        let mut state = GameState::new(
+            2,
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(1),
+        );
+        Classic.setup(&mut state);
+        let view = PlayerView::new(&Classic, &state, 0);
+
+        let mut strategy = WithColors::new(Box::new(DeclaresRed), Box::new(AlwaysBlue));
+        let action = strategy.choose(&[], &view);
+        assert!(matches!(
+            action,
+            Action::Play {
+                color: Some(ColorSuite::Blue),
+                ..
+            }
+        ));
+        assert_eq!(strategy.name(), "DeclaresRed + always-blue");
+    }
+}
@@ -1,50 +1,725 @@
-use crate::card::Card;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::Path,
+    sync::Arc,
+};
 
-#[derive(PartialEq)]
-enum UnoCondition {
-    PlusStack,
+use serde::Deserialize;
+
+use crate::{
+    action::Action,
+    algorithms::Greedy,
+    card::Card,
+    color::ColorSuite,
+    face::Face,
+    hand::Hand,
+    hand_analysis::{CardHistogram, HandAnalysis},
+    strategy::{Strategy, StrategyFactory},
+    view::PlayerView,
+};
+
+/// A group of cards a decision tree can ask about or play
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardClass {
+    #[default]
+    Any,
+    Number,
+
+    /// A colored skip, reverse or plus two
+    Action,
+    Skip,
+    Reverse,
+    PlusTwo,
+
+    /// A wild card other than a plus four
+    Wild,
+    PlusFour,
+
+    /// A plus two or a plus four
+    Plus,
+
+    /// Any card that is not wild
+    Colored,
 }
 
-struct DecisionNode<'a> {
-    decision: &'a Card,
-    branches: Vec<Branch<'a>>,
+impl CardClass {
+    /// Indicates whether `card` belongs to this class
+    pub fn contains(&self, card: &Card) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Number => card.is_num(),
+            Self::Action => {
+                !card.is_wild() && matches!(card.face, Face::Skip | Face::Reverse | Face::PlusTwo)
+            }
+            Self::Skip => card.face == Face::Skip,
+            Self::Reverse => card.face == Face::Reverse,
+            Self::PlusTwo => card.face == Face::PlusTwo,
+            Self::Wild => card.is_wild() && card.face != Face::PlusFour,
+            Self::PlusFour => card.face == Face::PlusFour,
+            Self::Plus => card.face.is_plus(),
+            Self::Colored => !card.is_wild(),
+        }
+    }
 }
 
-struct Branch<'a> {
-    condition: UnoCondition,
-    child: Box<Option<DecisionNode<'a>>>,
+/// Which of several cards of a class to play
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preference {
+    /// The card that leaves the most of the hand playable on top of it
+    #[default]
+    MostPlayable,
+
+    /// The card worth the most points, to shed them before someone goes out
+    MostPoints,
+    FewestPoints,
 }
 
-impl<'a> DecisionNode<'a> {
-    pub fn follow(&'a self, conditions: &[UnoCondition]) -> Option<&'a Card> {
-        let mut current_node = self;
+/// A color picked by what the game looks like
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ColorPick {
+    /// The color the hand holds the most of
+    #[default]
+    MostHeld,
 
-        loop {
-            // If node has no branches, it is a leaf and therefore a decision
-            if current_node.branches.is_empty() {
-                return Some(&current_node.decision);
+    /// The color out of the game's colors the hand holds the fewest of
+    LeastHeld,
+
+    /// The color of the top card of the discard pile
+    Top,
+    Named(ColorSuite),
+}
+
+impl ColorPick {
+    /// Returns the color this pick stands for when holding `hand`, if there is one
+    pub fn resolve(&self, hand: &Hand, view: &PlayerView) -> Option<ColorSuite> {
+        let histogram = CardHistogram::new(hand);
+        match self {
+            Self::MostHeld => histogram.max().map(|(color, _)| color),
+            Self::LeastHeld => view
+                .colors()
+                .iter()
+                .copied()
+                .min_by_key(|&color| histogram.color(color)),
+            Self::Top => view.top().color_suite(),
+            Self::Named(color) => Some(*color),
+        }
+    }
+}
+
+impl TryFrom<String> for ColorPick {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        match name.to_lowercase().as_str() {
+            "most_held" => Ok(Self::MostHeld),
+            "least_held" => Ok(Self::LeastHeld),
+            "top" => Ok(Self::Top),
+            _ => ColorSuite::named(&name).map(Self::Named).ok_or_else(|| {
+                format!(
+                    "unknown color {}, expected most_held, least_held, top or a color name",
+                    name
+                )
+            }),
+        }
+    }
+}
+
+/// A question about the game that sends a decision tree down one branch or the other
+#[derive(PartialEq, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnoCondition {
+    /// Plus cards are stacked up for this player to draw
+    PlusStack,
+
+    /// Some opponent holds at most this many cards
+    OpponentAtMost(usize),
+
+    /// The player moving next holds at most this many cards
+    NextAtMost(usize),
+
+    /// This player holds at most this many cards
+    HandAtMost(usize),
+
+    /// This player holds at least `at_least` colored cards of `color`.
+    /// Wild cards never count.
+    Holding {
+        color: ColorPick,
+        at_least: usize,
+    },
+
+    /// The top card of the discard pile belongs to the class
+    TopIs(CardClass),
+
+    /// The top card of the discard pile shows this number
+    TopNumber(u8),
+
+    /// Some legal move plays only cards of the class
+    CanPlay(CardClass),
+
+    All(Vec<UnoCondition>),
+    Any(Vec<UnoCondition>),
+    Not(Box<UnoCondition>),
+}
+
+impl UnoCondition {
+    /// Indicates whether the condition holds for the player of `view`
+    pub fn holds(&self, moves: &[Action], view: &PlayerView) -> bool {
+        let opponents = || {
+            view.hand_sizes()
+                .iter()
+                .enumerate()
+                .filter(move |&(seat, _)| seat != view.seat())
+                .map(|(_, &size)| size)
+        };
+        match self {
+            Self::PlusStack => view.stack_count() > 0,
+            Self::OpponentAtMost(n) => opponents().any(|size| size <= *n),
+            Self::NextAtMost(n) => {
+                view.next_seat() != view.seat() && view.hand_sizes()[view.next_seat()] <= *n
+            }
+            Self::HandAtMost(n) => view.hand().len() <= *n,
+            Self::Holding { color, at_least } => color
+                .resolve(view.hand(), view)
+                .is_some_and(|color| CardHistogram::new(view.hand()).color(color) >= *at_least),
+            Self::TopIs(class) => class.contains(view.top()),
+            Self::TopNumber(n) => {
+                matches!(view.top().face, Face::Number(m) | Face::Rank(m) if m == *n)
             }
+            Self::CanPlay(class) => moves.iter().any(|action| plays_only(action, *class)),
+            Self::All(conditions) => conditions.iter().all(|c| c.holds(moves, view)),
+            Self::Any(conditions) => conditions.iter().any(|c| c.holds(moves, view)),
+            Self::Not(condition) => !condition.holds(moves, view),
+        }
+    }
+
+    /// Checks that every condition it combines is there
+    fn validate(&self, path: &str) -> Result<(), TreeError> {
+        match self {
+            Self::All(conditions) | Self::Any(conditions) => {
+                let kind = if matches!(self, Self::All(_)) {
+                    "all"
+                } else {
+                    "any"
+                };
+                if conditions.is_empty() {
+                    return Err(TreeError::Invalid(
+                        path.to_owned(),
+                        format!("{} needs at least one condition", kind),
+                    ));
+                }
+                for (i, condition) in conditions.iter().enumerate() {
+                    condition.validate(&format!("{}.{}[{}]", path, kind, i))?;
+                }
+                Ok(())
+            }
+            Self::Not(condition) => condition.validate(&format!("{}.not", path)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Indicates whether `action` plays cards and every one of them belongs to `class`
+fn plays_only(action: &Action, class: CardClass) -> bool {
+    action.is_play() && action.cards().iter().all(|card| class.contains(card))
+}
+
+/// A move a leaf of a decision tree tries to make
+#[derive(PartialEq, Clone, Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Decision {
+    /// Plays the card of `class` that `prefer` likes best,
+    /// declaring `color` for a wild card
+    Play {
+        #[serde(default)]
+        class: CardClass,
+
+        #[serde(default)]
+        prefer: Preference,
+
+        #[serde(default)]
+        color: ColorPick,
+    },
+    Draw,
+    Pass,
+}
 
-            let matched_branch = current_node
-                .branches
+impl Decision {
+    /// Returns the legal move this decision makes, or `None` if none of `moves` fits it
+    pub fn make(&self, moves: &[Action], view: &PlayerView) -> Option<Action> {
+        match self {
+            Self::Play {
+                class,
+                prefer,
+                color,
+            } => {
+                // Ties go to the move listed first
+                let mut action = moves
+                    .iter()
+                    .filter(|action| plays_only(action, *class))
+                    .rev()
+                    .max_by_key(|action| rate(action.cards(), *prefer, view))?
+                    .clone();
+                if action.cards().iter().any(Card::is_wild) {
+                    let declared = declare(&action, *color, view);
+                    if let Action::Play { color, .. } | Action::Place { color, .. } = &mut action {
+                        *color = Some(declared);
+                    }
+                }
+                Some(action)
+            }
+            Self::Draw => moves
                 .iter()
-                .find(|branch| conditions.contains(&branch.condition));
+                .find(|&action| *action == Action::Draw)
+                .cloned(),
+            Self::Pass => moves
+                .iter()
+                .find(|&action| *action == Action::Pass)
+                .cloned(),
+        }
+    }
+}
+
+/// Rates playing `cards` by `prefer`, higher is better
+fn rate(cards: &[Card], prefer: Preference, view: &PlayerView) -> isize {
+    let points = cards.iter().map(Card::points).sum::<usize>() as isize;
+    match prefer {
+        Preference::MostPlayable => {
+            let mut rest = view.hand().clone();
+            for card in cards {
+                rest.remove_card(card);
+            }
+            let mut top = *cards.last().unwrap();
+            if top.color_suite().is_none() {
+                top.assign_color(declare_as(ColorPick::MostHeld, &rest, view));
+            }
+            HandAnalysis::new(&rest).playable_on(&top) as isize
+        }
+        Preference::MostPoints => points,
+        Preference::FewestPoints => -points,
+    }
+}
 
-            match matched_branch {
-                Some(branch) => {
-                    // Move to the child node indicated by the matched branch
-                    if let Some(child) = &*branch.child {
-                        current_node = child;
+/// Returns the color to declare for the wild cards `action` plays
+fn declare(action: &Action, pick: ColorPick, view: &PlayerView) -> ColorSuite {
+    let mut rest = view.hand().clone();
+    for card in action.cards() {
+        rest.remove_card(card);
+    }
+    declare_as(pick, &rest, view)
+}
+
+/// Resolves `pick` for a hand left holding `rest`.
+/// Falls back to the color held the most, then to the first color of the game,
+/// when the pick has no color or names one the game is not played with.
+fn declare_as(pick: ColorPick, rest: &Hand, view: &PlayerView) -> ColorSuite {
+    [pick, ColorPick::MostHeld]
+        .iter()
+        .filter_map(|pick| pick.resolve(rest, view))
+        .find(|color| view.colors().contains(color))
+        .unwrap_or(view.colors()[0])
+}
+
+/// A node of a decision tree.
+/// Branches ask a question and leaves list the moves to try, in order.
+#[derive(PartialEq, Clone, Debug)]
+pub enum DecisionNode {
+    Branch {
+        condition: UnoCondition,
+        then: Box<DecisionNode>,
+
+        /// Where to go when the condition fails, if anywhere
+        otherwise: Option<Box<DecisionNode>>,
+    },
+    Leaf(Vec<Decision>),
+}
+
+impl DecisionNode {
+    /// Walks down from this node and returns the decisions of the leaf it ends on,
+    /// or `None` if a failed condition has nowhere else to go
+    pub fn follow(&self, moves: &[Action], view: &PlayerView) -> Option<&[Decision]> {
+        let mut current_node = self;
+
+        loop {
+            match current_node {
+                Self::Leaf(decisions) => return Some(decisions),
+                Self::Branch {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    if condition.holds(moves, view) {
+                        current_node = then;
                     } else {
-                        // Child node is None, terminate traversal
-                        return None;
+                        current_node = otherwise.as_deref()?;
                     }
                 }
-                None => {
-                    // No branch matches the condition, terminate traversal
-                    return None;
+            }
+        }
+    }
+}
+
+/// A node as it is written in a tree file, before it is validated
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeSpec {
+    #[serde(rename = "if")]
+    condition: Option<UnoCondition>,
+    then: Option<Box<NodeSpec>>,
+
+    #[serde(rename = "else")]
+    otherwise: Option<Box<NodeSpec>>,
+
+    #[serde(rename = "do")]
+    decisions: Option<Vec<Decision>>,
+}
+
+impl NodeSpec {
+    /// Validates this node and everything below it.
+    /// `path` names the node in errors, such as `tree.else.then`.
+    fn validate(self, path: &str) -> Result<DecisionNode, TreeError> {
+        let invalid = |reason: &str| Err(TreeError::Invalid(path.to_owned(), reason.to_owned()));
+        match self {
+            Self {
+                condition: Some(condition),
+                then: Some(then),
+                otherwise,
+                decisions: None,
+            } => {
+                condition.validate(&format!("{}.if", path))?;
+                let then = then.validate(&format!("{}.then", path))?;
+                let otherwise = match otherwise {
+                    Some(node) => Some(Box::new(node.validate(&format!("{}.else", path))?)),
+                    None => None,
+                };
+                Ok(DecisionNode::Branch {
+                    condition,
+                    then: Box::new(then),
+                    otherwise,
+                })
+            }
+            Self {
+                condition: None,
+                then: None,
+                otherwise: None,
+                decisions: Some(decisions),
+            } => {
+                if decisions.is_empty() {
+                    return invalid("do needs at least one action");
                 }
+                Ok(DecisionNode::Leaf(decisions))
             }
+            Self {
+                decisions: Some(_), ..
+            } => invalid("a node either branches with if or decides with do, not both"),
+            Self {
+                condition: Some(_), ..
+            } => invalid("if needs a then"),
+            Self { then: Some(_), .. }
+            | Self {
+                otherwise: Some(_), ..
+            } => invalid("then and else need an if"),
+            _ => invalid("a node needs an if or a do"),
+        }
+    }
+}
+
+/// A tree file as it is written, before it is validated
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TreeSpec {
+    name: Option<String>,
+    tree: NodeSpec,
+}
+
+#[derive(Debug)]
+pub enum TreeError {
+    Io(io::Error),
+
+    /// The file is not valid JSON or TOML, or does not have the shape of a tree
+    Parse(String),
+
+    /// Where in the tree the problem is and what it is
+    Invalid(String, String),
+}
+
+impl Display for TreeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Parse(reason) => write!(f, "{}", reason),
+            Self::Invalid(path, reason) => write!(f, "{}: {}", path, reason),
         }
     }
 }
+
+impl Error for TreeError {}
+
+impl From<io::Error> for TreeError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// A strategy written as data rather than code, so that it can be authored without
+/// touching the crate. Every turn walks the tree from the root, answering the question
+/// of every branch, and makes the first move listed at the leaf it ends on that can be made.
+/// Trees that end nowhere, or on a leaf none of whose moves can be made, play like `Greedy`.
+///
+/// A tree file holds an optional `name` and the root node under `tree`.
+/// A branch has an `if` condition, a `then` node and an optional `else` node,
+/// and a leaf has a `do` list of moves. In JSON:
+///
+/// ```json
+/// {
+///     "name": "Stacker",
+///     "tree": {
+///         "if": "plus_stack",
+///         "then": { "do": [{ "action": "play", "class": "plus" }, { "action": "draw" }] },
+///         "else": {
+///             "if": { "opponent_at_most": 1 },
+///             "then": { "do": [{ "action": "play", "class": "plus_four", "color": "top" }] },
+///             "else": { "do": [{ "action": "play", "prefer": "most_points" }] }
+///         }
+///     }
+/// }
+/// ```
+///
+/// The conditions are `plus_stack`, `opponent_at_most`, `next_at_most`, `hand_at_most`,
+/// `holding` with a `color` and an `at_least`, `top_is` and `can_play` with a card class,
+/// `top_number`, and `all`, `any` and `not` to combine them.
+/// The card classes are `any`, `number`, `action`, `skip`, `reverse`, `plus_two`, `wild`,
+/// `plus_four`, `plus` and `colored`.
+/// A play may `prefer` `most_playable`, `most_points` or `fewest_points` and declares the
+/// `color` `most_held`, `least_held`, `top` or a color by name for a wild card.
+#[derive(Clone, Debug)]
+pub struct DecisionTree {
+    name: String,
+    root: DecisionNode,
+}
+
+impl DecisionTree {
+    /// # Arguments
+    ///
+    /// * `name` - The name of the strategy, printed alongside its wins
+    /// * `root` - The node every turn starts from
+    pub fn new(name: &str, root: DecisionNode) -> Self {
+        Self {
+            name: name.to_owned(),
+            root,
+        }
+    }
+
+    /// Reads a tree from a file, as TOML if its name ends in `.toml` and as JSON otherwise
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TreeError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            Self::from_toml(&text)
+        } else {
+            Self::from_json(&text)
+        }
+    }
+
+    /// Reads a tree written in JSON
+    pub fn from_json(text: &str) -> Result<Self, TreeError> {
+        let spec = serde_json::from_str(text).map_err(|err| TreeError::Parse(err.to_string()))?;
+        Self::from_spec(spec)
+    }
+
+    /// Reads a tree written in TOML
+    pub fn from_toml(text: &str) -> Result<Self, TreeError> {
+        let spec = toml::from_str(text).map_err(|err| TreeError::Parse(err.to_string()))?;
+        Self::from_spec(spec)
+    }
+
+    fn from_spec(spec: TreeSpec) -> Result<Self, TreeError> {
+        let root = spec.tree.validate("tree")?;
+        Ok(Self::new(
+            spec.name.as_deref().unwrap_or("DecisionTree"),
+            root,
+        ))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the move the tree makes, or `None` if it ends nowhere
+    /// or on a leaf none of whose moves can be made
+    pub fn decide(&self, moves: &[Action], view: &PlayerView) -> Option<Action> {
+        self.root
+            .follow(moves, view)?
+            .iter()
+            .find_map(|decision| decision.make(moves, view))
+    }
+
+    /// Returns a factory playing this tree in every game
+    pub fn factory(self) -> StrategyFactory {
+        let tree = Arc::new(self);
+        StrategyFactory::new(&tree.name.clone(), move || {
+            Box::new(TreePolicy::new(tree.clone()))
+        })
+    }
+}
+
+/// Plays the moves of a `DecisionTree`
+pub struct TreePolicy {
+    tree: Arc<DecisionTree>,
+    fallback: Greedy,
+}
+
+impl TreePolicy {
+    /// # Arguments
+    ///
+    /// * `tree` - The tree, which may be shared between games
+    pub fn new(tree: Arc<DecisionTree>) -> Self {
+        Self {
+            tree,
+            fallback: Greedy::new(2),
+        }
+    }
+}
+
+impl Strategy for TreePolicy {
+    fn name(&self) -> &str {
+        self.tree.name()
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        match self.tree.decide(moves, view) {
+            Some(action) => action,
+            None => {
+                log::debug!("seat {} fell off its decision tree", view.seat());
+                self.fallback.choose(moves, view)
+            }
+        }
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        self.fallback.choose_color(view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the path and reason a tree is rejected with
+    fn invalid(json: &str) -> (String, String) {
+        match DecisionTree::from_json(json) {
+            Err(TreeError::Invalid(path, reason)) => (path, reason),
+            Err(err) => panic!("expected an invalid tree, got {}", err),
+            Ok(_) => panic!("expected an invalid tree"),
+        }
+    }
+
+    #[test]
+    fn reads_the_same_tree_from_json_and_toml() {
+        let json = DecisionTree::from_json(
+            r#"{
+                "name": "Stacker",
+                "tree": {
+                    "if": { "not": "plus_stack" },
+                    "then": { "do": [{ "action": "play", "prefer": "most_points" }] },
+                    "else": { "do": [{ "action": "play", "class": "plus" }, { "action": "draw" }] }
+                }
+            }"#,
+        )
+        .unwrap();
+        let toml = DecisionTree::from_toml(
+            r#"
+            name = "Stacker"
+
+            [tree]
+            if = { not = "plus_stack" }
+            then = { do = [{ action = "play", prefer = "most_points" }] }
+            else = { do = [{ action = "play", class = "plus" }, { action = "draw" }] }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(json.name(), "Stacker");
+        assert_eq!(json.root, toml.root);
+        let DecisionNode::Branch { condition, .. } = &json.root else {
+            panic!("expected a branch at the root");
+        };
+        assert_eq!(
+            *condition,
+            UnoCondition::Not(Box::new(UnoCondition::PlusStack))
+        );
+    }
+
+    #[test]
+    fn the_example_tree_is_valid() {
+        let tree = DecisionTree::from_json(include_str!("../../console/decision-tree.json"));
+        assert_eq!(tree.unwrap().name(), "Stacker");
+    }
+
+    #[test]
+    fn names_where_a_tree_is_invalid() {
+        assert_eq!(
+            invalid(r#"{ "tree": { "if": "plus_stack" } }"#),
+            ("tree".to_owned(), "if needs a then".to_owned())
+        );
+        assert_eq!(
+            invalid(r#"{ "tree": { "then": { "do": [{ "action": "draw" }] } } }"#),
+            ("tree".to_owned(), "then and else need an if".to_owned())
+        );
+        assert_eq!(
+            invalid(r#"{ "tree": {} }"#),
+            ("tree".to_owned(), "a node needs an if or a do".to_owned())
+        );
+        assert_eq!(
+            invalid(r#"{ "tree": { "if": "plus_stack", "then": { "do": [] } } }"#),
+            (
+                "tree.then".to_owned(),
+                "do needs at least one action".to_owned()
+            )
+        );
+        assert_eq!(
+            invalid(
+                r#"{ "tree": { "if": "plus_stack", "then": { "do": [{ "action": "draw" }] },
+                    "do": [{ "action": "pass" }] } }"#
+            ),
+            (
+                "tree".to_owned(),
+                "a node either branches with if or decides with do, not both".to_owned()
+            )
+        );
+        assert_eq!(
+            invalid(
+                r#"{ "tree": {
+                    "if": "plus_stack",
+                    "then": { "do": [{ "action": "draw" }] },
+                    "else": {
+                        "if": { "all": [{ "hand_at_most": 2 }, { "not": { "any": [] } }] },
+                        "then": { "do": [{ "action": "pass" }] }
+                    }
+                } }"#
+            ),
+            (
+                "tree.else.if.all[1].not".to_owned(),
+                "any needs at least one condition".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_where_a_file_fails_to_parse() {
+        let err = DecisionTree::from_json("{\n  \"tree\": { \"if\": \"psychic\" }\n}")
+            .err()
+            .unwrap();
+        assert!(matches!(&err, TreeError::Parse(_)));
+        assert!(err.to_string().contains("line 2"), "{}", err);
+
+        let err = DecisionTree::from_toml("[tree]\ndo = [{ action = \"dance\" }]\n")
+            .err()
+            .unwrap();
+        assert!(matches!(&err, TreeError::Parse(_)));
+        assert!(err.to_string().contains("line 2"), "{}", err);
+    }
+}
@@ -1,8 +1,9 @@
-use core::fmt;
 use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use std::fmt;
 use std::{
     error::Error,
     fmt::{Display, Formatter},
+    ops::RangeInclusive,
 };
 
 use crate::{
@@ -14,58 +15,163 @@ use crate::{
 #[derive(Debug)]
 pub enum DeckError {
     NotEnoughCards(usize, usize),
+    InvalidSpec(String),
 }
 
 impl Display for DeckError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::NotEnoughCards(has, requested) => {
-                write!(f, "requested {} cards, deck only has {}", has, requested)
+                write!(f, "requested {} cards, deck only has {}", requested, has)
             }
+            Self::InvalidSpec(reason) => write!(f, "invalid deck: {}", reason),
         }
     }
 }
 
 impl Error for DeckError {}
 
-/// Represents the Uno deck that cards will be pulled from
-pub struct Deck {
-    stack: Vec<Card>,
-
-    rng: SmallRng,
+/// Describes the composition of an Uno deck.
+///
+/// Every color gets one zero (if zero is in range), two of every other number
+/// and two of each action card. There is one Wild and one Wild Draw Four per color.
+/// `DeckSpec::standard()` yields the official 108 card deck.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct DeckSpec {
+    colors: usize,
+    min_number: u8,
+    max_number: u8,
 }
 
-impl Deck {
-    /// Constructs a new standard unshuffled Uno deck
-    pub fn new() -> Self {
-        const UNO_DECK_SIZE: usize = 108;
-        let mut stack = Vec::with_capacity(UNO_DECK_SIZE);
-        for color in ColorSuite::all() {
-            stack.push(Card::new_colored(Face::Zero, color));
-            for face in Face::double_faces() {
+impl DeckSpec {
+    /// Constructs a spec with `colors` colors and numeric cards in `numbers`.
+    ///
+    /// # Returns
+    ///
+    /// `Err(DeckError)` if there are no colors, more than `ColorSuite::MAX_COLORS`
+    /// colors, or the number range is empty.
+    pub fn new(colors: usize, numbers: RangeInclusive<u8>) -> Result<Self, DeckError> {
+        if colors == 0 || colors > ColorSuite::MAX_COLORS {
+            return Err(DeckError::InvalidSpec(format!(
+                "a deck needs between 1 and {} colors, got {}",
+                ColorSuite::MAX_COLORS,
+                colors
+            )));
+        }
+        if numbers.is_empty() {
+            return Err(DeckError::InvalidSpec(format!(
+                "number range {}..={} is empty",
+                numbers.start(),
+                numbers.end()
+            )));
+        }
+        Ok(Self {
+            colors,
+            min_number: *numbers.start(),
+            max_number: *numbers.end(),
+        })
+    }
+
+    /// The official four color deck numbered 0 through 9
+    pub fn standard() -> Self {
+        Self {
+            colors: 4,
+            min_number: 0,
+            max_number: 9,
+        }
+    }
+
+    /// Returns the colors used by this deck.
+    pub fn colors(&self) -> &'static [ColorSuite] {
+        ColorSuite::palette(self.colors)
+    }
+
+    /// Returns the range of numbers printed on numeric cards.
+    pub fn numbers(&self) -> RangeInclusive<u8> {
+        self.min_number..=self.max_number
+    }
+
+    /// Returns how many times a number appears per color.
+    fn copies_of(number: u8) -> usize {
+        if number == 0 {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Returns the total amount of cards in a deck built from this spec.
+    pub fn size(&self) -> usize {
+        let numbers: usize = self.numbers().map(Self::copies_of).sum();
+        let actions = Face::actions().len() * 2;
+        self.colors * (numbers + actions + 2)
+    }
+
+    /// Builds every card of this spec in an unshuffled order.
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards = Vec::with_capacity(self.size());
+        for &color in self.colors() {
+            for n in self.numbers() {
+                for _ in 0..Self::copies_of(n) {
+                    cards.push(Card::new_colored(Face::Number(n), color));
+                }
+            }
+            for face in Face::actions() {
                 for _ in 0..2 {
-                    stack.push(Card::new_colored(face, color));
+                    cards.push(Card::new_colored(face, color));
                 }
             }
         }
-        for _ in 0..4 {
+        for _ in 0..self.colors {
             for wild_face in [Face::Wild, Face::PlusFour] {
-                stack.push(Card::new_wild(wild_face))
+                cards.push(Card::new_wild(wild_face))
             }
         }
+        cards
+    }
+}
+
+impl Default for DeckSpec {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// Represents the Uno deck that cards will be pulled from
+#[derive(Clone)]
+pub struct Deck {
+    stack: Vec<Card>,
+
+    rng: SmallRng,
+}
+
+impl Deck {
+    /// Constructs a new unshuffled deck holding exactly `cards`.
+    /// The last card is the top of the deck.
+    pub fn from_cards(cards: Vec<Card>) -> Self {
         Self {
-            stack,
+            stack: cards,
             rng: SmallRng::from_entropy(),
         }
     }
 
-    /// Constructs a new standard shuffled Uno deck
-    pub fn new_shuffled() -> Self {
-        let mut deck = Self::new();
+    /// Constructs a new shuffled deck holding `cards`
+    pub fn new_shuffled(cards: Vec<Card>) -> Self {
+        let mut deck = Self::from_cards(cards);
         deck.shuffle();
         deck
     }
 
+    /// Makes every following shuffle depend on `seed` alone
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+    }
+
+    /// Returns the cards in the deck. The last card is the top of the deck.
+    pub fn cards(&self) -> &[Card] {
+        &self.stack
+    }
+
     /// Shuffles the Deck
     pub fn shuffle(&mut self) {
         self.stack.shuffle(&mut self.rng);
@@ -103,6 +209,11 @@ impl Deck {
         self.stack = cards;
     }
 
+    /// Returns the amount of cards left in the deck.
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
     /// Indicates whether the deck contains no cards.
     pub fn is_empty(&self) -> bool {
         self.stack.is_empty()
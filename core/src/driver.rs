@@ -0,0 +1,337 @@
+use std::{thread, time::Duration};
+
+use crate::{
+    action::Action,
+    color::ColorSuite,
+    event::{GameEvent, Observer},
+    options::GameOptions,
+    state::{GameState, TurnState},
+    strategy::Strategy,
+    variants::{Ruleset, TurnStatus},
+    view::PlayerView,
+};
+
+/// Steps a game from turn to turn, leaving every rule up to its `Ruleset`.
+/// Seats with a strategy make their own moves, while a seat without one
+/// hands control back to the caller whenever it has a move to make,
+/// so that `Game`, `UnoEnv` and `Simulation` play out turns the same way.
+pub(crate) struct TurnDriver {
+    ruleset: &'static dyn Ruleset,
+    state: GameState,
+
+    /// A fresh strategy for every seat, with `None` where the caller makes the moves
+    strategies: Vec<Option<Box<dyn Strategy>>>,
+
+    /// Names every seat in the message of an illegal move
+    names: Vec<String>,
+
+    /// Everyone besides the strategies who is told about the events of the game
+    observers: Vec<Box<dyn Observer>>,
+
+    turn_pause: Duration,
+
+    /// Whether strategies may see the hidden cards, see `Options::perfect_information`
+    perfect_information: bool,
+
+    /// The seat whose turn is in progress, if one is
+    current: Option<usize>,
+
+    /// Whether the seat in turn played any cards so far
+    played: bool,
+
+    /// The turns in a row where nobody could play or draw
+    stalled_turns: usize,
+
+    /// The turns finished so far
+    turns: usize,
+
+    winner: Option<usize>,
+}
+
+impl TurnDriver {
+    /// # Arguments
+    ///
+    /// * `ruleset` - Runs the game
+    /// * `state` - The game, about to be set up
+    /// * `strategies` - The strategy of every seat, with `None` where the caller makes the moves
+    /// * `names` - The name of every seat, used when a strategy makes an illegal move
+    pub(crate) fn new(
+        ruleset: &'static dyn Ruleset,
+        state: GameState,
+        strategies: Vec<Option<Box<dyn Strategy>>>,
+        names: Vec<String>,
+    ) -> Self {
+        Self {
+            ruleset,
+            state,
+            strategies,
+            names,
+            observers: Vec::new(),
+            turn_pause: Duration::ZERO,
+            perfect_information: false,
+            current: None,
+            played: false,
+            stalled_turns: 0,
+            turns: 0,
+            winner: None,
+        }
+    }
+
+    /// Picks a game up in the middle of the turn of `seat`, without any strategies,
+    /// so that the caller makes every move
+    pub(crate) fn resume(ruleset: &'static dyn Ruleset, state: GameState, seat: usize) -> Self {
+        let players = state.hands.len();
+        let played = !state.turn.played.is_empty() || !state.turn.placed.is_empty();
+        let strategies = (0..players).map(|_| None).collect();
+        let names = (0..players).map(|seat| format!("seat {}", seat)).collect();
+        let mut driver = Self::new(ruleset, state, strategies, names);
+        driver.current = Some(seat);
+        driver.played = played;
+        driver
+    }
+
+    /// Copies the game along with the turn in progress,
+    /// leaving the strategies and observers behind
+    pub(crate) fn fork(&self) -> Self {
+        Self {
+            ruleset: self.ruleset,
+            state: self.state.clone(),
+            strategies: self.strategies.iter().map(|_| None).collect(),
+            names: self.names.clone(),
+            observers: Vec::new(),
+            turn_pause: self.turn_pause,
+            perfect_information: self.perfect_information,
+            current: self.current,
+            played: self.played,
+            stalled_turns: self.stalled_turns,
+            turns: self.turns,
+            winner: self.winner,
+        }
+    }
+
+    /// Takes the pause between turns and the perfect information setting from `options`
+    pub(crate) fn with_options(mut self, options: &GameOptions) -> Self {
+        self.turn_pause = options.turn_pause;
+        self.perfect_information = options.perfect_information;
+        self
+    }
+
+    /// Registers an observer that is told about every event of the game
+    pub(crate) fn add_observer(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer)
+    }
+
+    pub(crate) fn ruleset(&self) -> &'static dyn Ruleset {
+        self.ruleset
+    }
+
+    pub(crate) fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub(crate) fn state_mut(&mut self) -> &mut GameState {
+        &mut self.state
+    }
+
+    /// The seat whose turn is in progress, if one is
+    pub(crate) fn seat(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// The amount of turns finished so far
+    pub(crate) fn turns(&self) -> usize {
+        self.turns
+    }
+
+    /// Whether the seat in turn played any cards so far
+    pub(crate) fn played(&self) -> bool {
+        self.played
+    }
+
+    /// The turns in a row where nobody could play or draw
+    pub(crate) fn stalled_turns(&self) -> usize {
+        self.stalled_turns
+    }
+
+    /// The seat that won, if the game is over
+    pub(crate) fn winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    /// Tells every strategy the game starts, sets it up and lets the first player
+    /// declare the color of a wild card turned over during setup.
+    /// The color is left up to chance for a seat without a strategy.
+    pub(crate) fn start(&mut self) {
+        for (seat, strategy) in self.strategies.iter_mut().enumerate() {
+            if let Some(strategy) = strategy {
+                strategy.on_game_start(seat, &self.state.rules);
+            }
+        }
+        self.ruleset.setup(&mut self.state);
+
+        let first = *self.state.order.peek_next();
+        for target in self.state.undeclared_targets() {
+            let color = match self.strategies[first].as_mut() {
+                Some(strategy) => strategy.choose_color(
+                    &PlayerView::new(self.ruleset, &self.state, first)
+                        .with_perfect_information(self.perfect_information),
+                ),
+                None => None,
+            };
+            // A color the game is not played with is left up to chance as well
+            let color = color.filter(|color| self.state.colors.contains(color));
+            self.state.declare_target(first, target, color);
+        }
+        self.publish();
+    }
+
+    /// Plays turns until a seat without a strategy has a move to make or the game is over.
+    /// Returns the seat that has to move, or `None` once there is a winner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a strategy makes an illegal move,
+    /// which includes declaring a color the game is not played with
+    pub(crate) fn advance(&mut self) -> Option<usize> {
+        loop {
+            if self.winner.is_some() {
+                return None;
+            }
+            let seat = match self.current {
+                Some(seat) => seat,
+                None => {
+                    let players = self.state.hands.len();
+                    if self.stalled_turns >= players {
+                        // Nobody can play and every card is in a hand,
+                        // so the game is blocked and the smallest hand wins
+                        let hands = &self.state.hands;
+                        let winner = (0..players).min_by_key(|&seat| hands[seat].len()).unwrap();
+                        self.finish(winner, true);
+                        return None;
+                    }
+                    let seat = *self.state.order.next();
+                    self.state.turn = TurnState::default();
+                    self.current = Some(seat);
+                    self.played = false;
+                    seat
+                }
+            };
+
+            let moves = self.ruleset.legal_moves(&self.state, seat);
+            if moves.is_empty() {
+                self.end_turn();
+                continue;
+            }
+            let Some(strategy) = self.strategies[seat].as_mut() else {
+                return Some(seat);
+            };
+            let view = PlayerView::new(self.ruleset, &self.state, seat)
+                .with_perfect_information(self.perfect_information);
+            let action = strategy.choose(&moves, &view);
+            if !is_legal(&moves, &action, self.state.colors) {
+                panic!(
+                    "{} tried to make the illegal move {:?} using the {} strategy",
+                    self.names[seat],
+                    action,
+                    strategy.name()
+                );
+            }
+            self.apply(action);
+        }
+    }
+
+    /// Makes a move for the seat in turn, ending the turn once it is over
+    ///
+    /// # Panics
+    ///
+    /// Panics if no turn is in progress
+    pub(crate) fn apply(&mut self, action: Action) {
+        let seat = self.current.expect("no turn is in progress");
+        self.played |= action.is_play();
+        let status = self.ruleset.apply(&mut self.state, seat, action);
+        self.publish();
+        if status == TurnStatus::Over || self.ruleset.winner(&self.state).is_some() {
+            self.end_turn();
+        }
+    }
+
+    /// Hands every event recorded since the last call to the observers and strategies
+    fn publish(&mut self) {
+        for event in self.state.take_events() {
+            for observer in self.observers.iter_mut() {
+                observer.on_event(&event);
+            }
+            for strategy in self.strategies.iter_mut().flatten() {
+                strategy.on_event(&event);
+            }
+        }
+    }
+
+    /// Ends the turn in progress and checks whether the game is over
+    fn end_turn(&mut self) {
+        let Some(seat) = self.current.take() else {
+            return;
+        };
+        self.ruleset.end_turn(&mut self.state, seat);
+        self.turns += 1;
+        if self.played || self.state.turn.drawn > 0 {
+            self.stalled_turns = 0;
+        } else {
+            self.stalled_turns += 1;
+        }
+        self.publish();
+        if let Some(winner) = self.ruleset.winner(&self.state) {
+            self.finish(winner, false);
+        } else if !self.turn_pause.is_zero() {
+            thread::sleep(self.turn_pause)
+        }
+    }
+
+    /// Announces the winner and lets every strategy know who won
+    fn finish(&mut self, winner: usize, blocked: bool) {
+        self.winner = Some(winner);
+        self.state.emit(GameEvent::Won {
+            seat: winner,
+            blocked,
+        });
+        self.publish();
+        for (seat, strategy) in self.strategies.iter_mut().enumerate() {
+            if let Some(strategy) = strategy {
+                strategy.on_game_end(seat == winner);
+            }
+        }
+    }
+}
+
+/// Indicates whether `action` is one of `moves`,
+/// declaring one of `colors` if it declares a color at all
+fn is_legal(moves: &[Action], action: &Action, colors: &[ColorSuite]) -> bool {
+    let declared = match action {
+        Action::Play { color, .. } | Action::Place { color, .. } => *color,
+        _ => None,
+    };
+    moves.iter().any(|legal| legal.same_move(action))
+        && declared.is_none_or(|color| colors.contains(&color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::Card, face::Face};
+
+    #[test]
+    fn a_move_must_declare_a_color_of_the_game() {
+        let wild = Card::new_wild(Face::Wild);
+        let moves = [Action::play(wild), Action::Draw];
+        let declaring = |color| Action::Play {
+            target: 0,
+            cards: vec![wild],
+            color: Some(color),
+        };
+        let colors = ColorSuite::standard();
+        assert!(is_legal(&moves, &Action::play(wild), colors));
+        assert!(is_legal(&moves, &declaring(ColorSuite::Blue), colors));
+        assert!(!is_legal(&moves, &declaring(ColorSuite::Hearts), colors));
+        assert!(!is_legal(&moves, &Action::Pass, colors));
+    }
+}
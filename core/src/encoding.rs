@@ -0,0 +1,193 @@
+use crate::{
+    action::Action, card::Card, card_counter::as_dealt, color::ColorSuite,
+    reversible_ring::Direction, view::PlayerView,
+};
+
+/// A fixed-size numeric encoding of what a player can see, see `Encoding::encode`
+pub type Observation = Vec<f32>;
+
+/// Turns what a player can see into numbers and numbers into moves,
+/// for policies trained outside the crate.
+/// The encoding only depends on the deck, the colors and the amount of players,
+/// so the same game always encodes the same way.
+///
+/// Moves are numbered by a fixed index space, see `Encoding::action`.
+/// Plays of several cards at once and plays onto targets other than the first,
+/// as Dos allows, have no index.
+#[derive(Clone, Debug)]
+pub struct Encoding {
+    /// Every distinct card of the deck as dealt, in sorted order
+    cards: Vec<Card>,
+    colors: Vec<ColorSuite>,
+    players: usize,
+
+    /// Every move that has an index, in index order
+    actions: Vec<Action>,
+}
+
+impl Encoding {
+    /// # Arguments
+    ///
+    /// * `composition` - Every card the game is played with
+    /// * `colors` - The colors wild cards can be declared as
+    /// * `players` - The amount of players
+    pub fn new(composition: &[Card], colors: &[ColorSuite], players: usize) -> Self {
+        let mut cards: Vec<Card> = composition.iter().map(as_dealt).collect();
+        cards.sort();
+        cards.dedup();
+
+        // A play and a placement of every card, once for every color of a wild card
+        let mut plays = Vec::new();
+        for &card in cards.iter() {
+            if card.is_wild() {
+                plays.extend(colors.iter().map(|&color| (card, Some(color))));
+            } else {
+                plays.push((card, None));
+            }
+        }
+        let mut actions: Vec<Action> = plays
+            .iter()
+            .map(|&(card, color)| Action::Play {
+                target: 0,
+                cards: vec![card],
+                color,
+            })
+            .collect();
+        actions.extend(
+            plays
+                .iter()
+                .map(|&(card, color)| Action::Place { card, color }),
+        );
+        actions.push(Action::Draw);
+        actions.push(Action::Pass);
+        actions.extend((0..players).map(|seat| Action::Swap { seat }));
+
+        Self {
+            cards,
+            colors: colors.to_vec(),
+            players,
+            actions,
+        }
+    }
+
+    /// Constructs the encoding of the game `view` is taken from
+    pub fn of(view: &PlayerView) -> Self {
+        Self::new(view.composition(), view.colors(), view.players())
+    }
+
+    /// The amount of players
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// The colors of the color block of every observation, in order
+    pub fn colors(&self) -> &[ColorSuite] {
+        &self.colors
+    }
+
+    /// The amount of action indices
+    pub fn action_count(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns the move numbered `index`.
+    /// Plays come first, one for every distinct card in sorted order and
+    /// one for every color of a wild card, followed by placing each of those cards,
+    /// then drawing, passing and swapping hands with every seat.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below `action_count`
+    pub fn action(&self, index: usize) -> &Action {
+        &self.actions[index]
+    }
+
+    /// Returns the index of `action`, if it has one.
+    /// A wild card played without a declared color has no index.
+    pub fn index_of(&self, action: &Action) -> Option<usize> {
+        self.actions.iter().position(|other| other == action)
+    }
+
+    /// Returns whether every action index is one of the legal `moves`
+    pub fn legal_mask(&self, moves: &[Action]) -> Vec<bool> {
+        self.actions
+            .iter()
+            .map(|action| {
+                moves
+                    .iter()
+                    .any(|legal| legal.same_move(action) && declares(legal, action))
+            })
+            .collect()
+    }
+
+    /// The length of every observation
+    pub fn observation_size(&self) -> usize {
+        3 * self.cards.len() + self.colors.len() + self.players + 4
+    }
+
+    /// Encodes what the player of `view` can see as `observation_size` numbers:
+    ///
+    /// * the copies of every distinct card in the player's hand
+    /// * the top card of the discard pile, one-hot over the distinct cards
+    /// * the color to match, one-hot over the colors
+    /// * the copies of every distinct card face up on the table
+    /// * the hand size of every seat, starting with the player's and going around the table
+    /// * the direction of play, 1 for clockwise and -1 otherwise
+    /// * the cards waiting in a plus stack
+    /// * the cards left in the deck
+    /// * the cards the player drew this turn
+    pub fn encode(&self, view: &PlayerView) -> Observation {
+        let mut observation = vec![0.0; self.observation_size()];
+        let n = self.cards.len();
+        let slot = |card: &Card| self.cards.binary_search(&as_dealt(card)).ok();
+
+        for card in view.hand() {
+            if let Some(i) = slot(card) {
+                observation[i] += 1.0;
+            }
+        }
+        let top = view.top();
+        if let Some(i) = slot(top) {
+            observation[n + i] = 1.0;
+        }
+        if let Some(i) = top
+            .color_suite()
+            .and_then(|color| self.colors.iter().position(|&other| other == color))
+        {
+            observation[2 * n + i] = 1.0;
+        }
+        let offset = 2 * n + self.colors.len();
+        for card in view.face_up() {
+            if let Some(i) = slot(&card) {
+                observation[offset + i] += 1.0;
+            }
+        }
+
+        let offset = offset + n;
+        let sizes = view.hand_sizes();
+        for i in 0..self.players {
+            observation[offset + i] = sizes[(view.seat() + i) % self.players] as f32;
+        }
+        let offset = offset + self.players;
+        observation[offset] = if view.direction() == Direction::Clockwise {
+            1.0
+        } else {
+            -1.0
+        };
+        observation[offset + 1] = view.stack_count() as f32;
+        observation[offset + 2] = view.cards_left() as f32;
+        observation[offset + 3] = view.drawn() as f32;
+        observation
+    }
+}
+
+/// Indicates whether `action` declares a color that `legal` allows.
+/// Legal moves leave the color of a wild card open, so any color is allowed for them.
+fn declares(legal: &Action, action: &Action) -> bool {
+    match (legal, action) {
+        (Action::Play { color: None, .. }, _) | (Action::Place { color: None, .. }, _) => true,
+        (Action::Play { color, .. }, Action::Play { color: other, .. })
+        | (Action::Place { color, .. }, Action::Place { color: other, .. }) => color == other,
+        _ => true,
+    }
+}
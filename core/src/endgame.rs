@@ -0,0 +1,525 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    action::Action,
+    card::Card,
+    color::ColorSuite,
+    event::GameEvent,
+    rules::Rules,
+    search::with_colors,
+    simulation::{PositionKey, Simulation},
+    strategy::Strategy,
+    view::PlayerView,
+};
+
+/// The most positions a line of an exact search goes through before it gives up,
+/// so that the search never runs out of stack
+const MAX_LINE: usize = 128;
+
+/// Searches two player endgames by expectiminimax.
+/// Both players are assumed to play perfectly, and every draw is a chance node
+/// over the cards left in the deck, weighted by how many copies of each it holds.
+/// Positions reached in different ways are only solved once.
+///
+/// By default the search is exact: every line is played out to the end
+/// and every order the drawn cards can come in is enumerated, a card at a time
+/// for as long as the move keeps drawing. A reshuffle is taken to happen before the move,
+/// so cards cleared off the table by the move itself are left out of it,
+/// and the color of a wild card turned over by the game is left up to chance.
+/// A line that comes back to a position it already went through counts as even.
+/// Since exact searches grow quickly with the deck, the search gives up
+/// once it went through more than `max_positions` positions,
+/// or once a line goes through more than `MAX_LINE` of them.
+///
+/// `EndgameSolver::depth_limited` trades exactness for speed instead.
+pub struct EndgameSolver {
+    /// Cuts the search short, see `EndgameSolver::depth_limited`
+    limit: Option<DepthLimit>,
+
+    /// The most positions an exact search goes through before it gives up
+    max_positions: usize,
+
+    rng: SmallRng,
+
+    /// The seat the win chances are for
+    root: usize,
+
+    /// The value of every position solved so far, along with the depth left if it is limited
+    memo: HashMap<(PositionKey, Option<usize>), f64>,
+
+    /// The positions the search is in the middle of
+    open: HashSet<PositionKey>,
+
+    /// Whether the search went through more than `max_positions` positions
+    gave_up: bool,
+}
+
+/// How a depth-limited search cuts its lines short
+#[derive(Copy, Clone)]
+struct DepthLimit {
+    /// How many turns are looked ahead
+    max_depth: usize,
+
+    /// How many deck shuffles a draw of several cards is averaged over
+    chance_samples: usize,
+}
+
+impl EndgameSolver {
+    /// A solver that searches exactly
+    ///
+    /// # Arguments
+    ///
+    /// * `max_positions` - The most positions a search goes through before it gives up
+    /// * `seed` - Seeds the deals of `solve_view` and the colors of wild cards the game turns over
+    pub fn exact(max_positions: usize, seed: u64) -> Self {
+        Self {
+            limit: None,
+            max_positions,
+            rng: SmallRng::seed_from_u64(seed),
+            root: 0,
+            memo: HashMap::new(),
+            open: HashSet::new(),
+            gave_up: false,
+        }
+    }
+
+    /// A solver that estimates rather than searching exactly.
+    /// Lines longer than `max_depth` turns are cut off with an estimate based on the hand sizes,
+    /// and draws of several cards at once, such as a plus stack or drawing until a match,
+    /// are averaged over `chance_samples` shuffles of the deck.
+    /// It never gives up.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_depth` - How many turns are looked ahead
+    /// * `chance_samples` - How many deck shuffles a draw of several cards is averaged over
+    /// * `seed` - Seeds the shuffles and deals, so that results can be reproduced
+    pub fn depth_limited(max_depth: usize, chance_samples: usize, seed: u64) -> Self {
+        Self {
+            limit: Some(DepthLimit {
+                max_depth: max_depth.max(1),
+                chance_samples: chance_samples.max(1),
+            }),
+            ..Self::exact(usize::MAX, seed)
+        }
+    }
+
+    /// Returns the chance that the seat in turn wins after each of its moves,
+    /// with every card in `simulation` known to both players,
+    /// or `None` if an exact search gave up.
+    /// Wild cards are listed once for every color they can be declared as.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the game has two players
+    pub fn solve(&mut self, simulation: &Simulation) -> Option<Vec<(Action, f64)>> {
+        let players = simulation.hand_sizes().len();
+        if players != 2 {
+            panic!("the endgame solver needs two players, got {}", players);
+        }
+        self.root = simulation.seat();
+        self.memo.clear();
+        self.open.clear();
+        self.gave_up = false;
+        let depth = self.limit.map(|limit| limit.max_depth);
+        let results = with_colors(&simulation.legal_moves(), simulation.colors())
+            .into_iter()
+            .map(|action| {
+                let chance = self.outcome(simulation, &action, depth);
+                (action, chance)
+            })
+            .collect();
+        (!self.gave_up).then_some(results)
+    }
+
+    /// Returns the chance that the player of `view` wins after each of their moves,
+    /// or `None` if an exact search gave up.
+    /// The cards they can't see are dealt `deals` times from the unseen cards,
+    /// every deal is solved as if both players knew it and the results are averaged.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the game has two players
+    pub fn solve_view(&mut self, view: &PlayerView, deals: usize) -> Option<Vec<(Action, f64)>> {
+        let mut totals: Vec<(Action, f64)> = Vec::new();
+        let deals = deals.max(1);
+        for _ in 0..deals {
+            let simulation = view.determinize(&mut self.rng);
+            let results = self.solve(&simulation)?;
+            if totals.is_empty() {
+                totals = results;
+            } else {
+                // Every deal leaves the player's own moves the same
+                for ((_, total), (_, chance)) in totals.iter_mut().zip(results) {
+                    *total += chance;
+                }
+            }
+        }
+        for (_, total) in totals.iter_mut() {
+            *total /= deals as f64;
+        }
+        Some(totals)
+    }
+
+    /// Returns the chance that the root seat wins from `simulation` on,
+    /// looking `depth` turns ahead if the search is limited
+    fn value(&mut self, simulation: &Simulation, depth: Option<usize>) -> f64 {
+        if let Some(winner) = simulation.winner() {
+            return if winner == self.root { 1.0 } else { 0.0 };
+        }
+        if depth == Some(0) {
+            // Whoever holds fewer cards is more likely to go out first
+            let sizes = simulation.hand_sizes();
+            let own = sizes[self.root] as f64;
+            let other = sizes[1 - self.root] as f64;
+            return other / (own + other);
+        }
+        if self.gave_up {
+            return 0.0;
+        }
+        let key = simulation.key();
+        if let Some(&value) = self.memo.get(&(key.clone(), depth)) {
+            return value;
+        }
+        if depth.is_none() && !self.open.insert(key.clone()) {
+            return 0.5;
+        }
+        if self.open.len() > MAX_LINE {
+            self.gave_up = true;
+            self.open.remove(&key);
+            return 0.0;
+        }
+
+        let maximizing = simulation.seat() == self.root;
+        let moves = with_colors(&simulation.legal_moves(), simulation.colors());
+        let mut value: f64 = if maximizing { 0.0 } else { 1.0 };
+        for action in moves.iter() {
+            let outcome = self.outcome(simulation, action, depth);
+            value = if maximizing {
+                value.max(outcome)
+            } else {
+                value.min(outcome)
+            };
+            // No other move can do better than a sure win or loss
+            if value == if maximizing { 1.0 } else { 0.0 } {
+                break;
+            }
+        }
+        self.open.remove(&key);
+        self.memo.insert((key, depth), value);
+        if self.memo.len() > self.max_positions {
+            self.gave_up = true;
+        }
+        value
+    }
+
+    /// Returns the depth left after `next` was reached from `simulation`.
+    /// Only moves that end a turn count.
+    fn depth_after(
+        simulation: &Simulation,
+        next: &Simulation,
+        depth: Option<usize>,
+    ) -> Option<usize> {
+        if next.winner().is_some() || next.seat() != simulation.seat() {
+            depth.map(|depth| depth - 1)
+        } else {
+            depth
+        }
+    }
+
+    /// Returns the chance that the root seat wins after `action`,
+    /// averaged over every card the move could draw
+    fn outcome(&mut self, simulation: &Simulation, action: &Action, depth: Option<usize>) -> f64 {
+        match self.limit {
+            None => self.drawing(simulation, action, &mut Vec::new()),
+            Some(limit) => self.sampled_outcome(simulation, action, depth, limit),
+        }
+    }
+
+    /// Returns the chance that the root seat wins after `action`,
+    /// averaged over every order the deck can be in that starts with `drawn`.
+    /// Every card the move draws beyond `drawn` is a chance node of its own.
+    fn drawing(&mut self, simulation: &Simulation, action: &Action, drawn: &mut Vec<Card>) -> f64 {
+        if self.gave_up {
+            return 0.0;
+        }
+        let mut next = simulation.clone();
+        next.draw_first(drawn);
+        next.apply(action.clone());
+        if next.taken() - simulation.taken() <= drawn.len() {
+            return self.value(&next, None);
+        }
+
+        let mut counts = simulation.deck_counts();
+        for card in drawn.iter() {
+            if let Some((_, count)) = counts.iter_mut().find(|(other, _)| other == card) {
+                *count -= 1;
+            }
+        }
+        counts.retain(|&(_, count)| count > 0);
+        if counts.is_empty() {
+            // The move draws past the end of the deck, so the table is reshuffled into it first
+            let mut reshuffled = simulation.clone();
+            reshuffled.reshuffle();
+            if reshuffled.cards_left() == simulation.cards_left() {
+                // Only cards cleared off the table by the move itself were left to draw
+                return self.value(&next, None);
+            }
+            return self.drawing(&reshuffled, action, drawn);
+        }
+
+        let total: usize = counts.iter().map(|(_, count)| count).sum();
+        let mut value = 0.0;
+        for (card, count) in counts {
+            drawn.push(card);
+            value += count as f64 / total as f64 * self.drawing(simulation, action, drawn);
+            drawn.pop();
+        }
+        value
+    }
+
+    /// Returns the chance that the root seat wins after `action` in a depth-limited search.
+    /// Single card draws are enumerated, while draws of several cards are sampled.
+    fn sampled_outcome(
+        &mut self,
+        simulation: &Simulation,
+        action: &Action,
+        depth: Option<usize>,
+        limit: DepthLimit,
+    ) -> f64 {
+        let mut probe = simulation.clone();
+        probe.apply(action.clone());
+        let drawn = simulation.cards_left().checked_sub(probe.cards_left());
+        match drawn {
+            Some(0) => {
+                let depth = Self::depth_after(simulation, &probe, depth);
+                self.value(&probe, depth)
+            }
+            Some(1) => {
+                let counts = simulation.deck_counts();
+                let total: usize = counts.iter().map(|(_, count)| count).sum();
+                let mut value = 0.0;
+                let reshuffle = simulation.rules().draw_until_match;
+                for (card, count) in counts {
+                    let mut next = simulation.clone();
+                    if reshuffle {
+                        // Anything drawn after the first card comes from a fresh shuffle
+                        next.shuffle_deck(self.rng.gen());
+                    }
+                    next.draw_first(&[card]);
+                    next.apply(action.clone());
+                    let depth = Self::depth_after(simulation, &next, depth);
+                    value += count as f64 / total as f64 * self.value(&next, depth);
+                }
+                value
+            }
+            // Several cards were drawn, or the deck was shuffled to draw them
+            _ => {
+                let mut value = 0.0;
+                for _ in 0..limit.chance_samples {
+                    let mut next = simulation.clone();
+                    next.shuffle_deck(self.rng.gen());
+                    next.apply(action.clone());
+                    let depth = Self::depth_after(simulation, &next, depth);
+                    value += self.value(&next, depth);
+                }
+                value / limit.chance_samples as f64
+            }
+        }
+    }
+}
+
+/// Plays like another strategy until both hands of a two player game
+/// are down to `threshold` cards, then plays the moves an `EndgameSolver` rates best.
+/// The solver looks at the hidden cards when the game is run with
+/// `Options::perfect_information`, and deals them at random otherwise.
+/// Positions the solver gives up on are left to the other strategy.
+pub struct Endgame {
+    name: String,
+    inner: Box<dyn Strategy>,
+
+    /// Hand size at or below which the solver takes over
+    threshold: usize,
+
+    /// How many deals of the hidden cards every decision is averaged over
+    deals: usize,
+
+    solver: EndgameSolver,
+}
+
+impl Endgame {
+    /// # Arguments
+    ///
+    /// * `inner` - Plays every move before the endgame
+    /// * `threshold` - Hand size at or below which the solver takes over
+    /// * `deals` - How many deals of the hidden cards every decision is averaged over
+    /// * `solver` - Solves the endgame positions
+    pub fn new(
+        inner: Box<dyn Strategy>,
+        threshold: usize,
+        deals: usize,
+        solver: EndgameSolver,
+    ) -> Self {
+        Self {
+            name: format!("{} + endgame", inner.name()),
+            inner,
+            threshold,
+            deals,
+            solver,
+        }
+    }
+
+    /// Indicates whether the solver plays this position
+    fn takes_over(&self, view: &PlayerView) -> bool {
+        view.players() == 2 && view.hand_sizes().iter().all(|&size| size <= self.threshold)
+    }
+}
+
+impl Strategy for Endgame {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn on_game_start(&mut self, seat: usize, rules: &Rules) {
+        self.inner.on_game_start(seat, rules)
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        if !self.takes_over(view) {
+            return self.inner.choose(moves, view);
+        }
+        let results = match view.reveal() {
+            Some(simulation) => self.solver.solve(&simulation),
+            None => self.solver.solve_view(view, self.deals),
+        };
+        let Some(results) = results else {
+            log::debug!("seat {} gave up searching the endgame", view.seat());
+            return self.inner.choose(moves, view);
+        };
+        for (action, chance) in results.iter() {
+            log::debug!(
+                "seat {} wins {:.1}% of the time after {:?}",
+                view.seat(),
+                chance * 100.0,
+                action
+            );
+        }
+        // Ties go to the move listed first
+        results
+            .into_iter()
+            .rev()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(action, _)| action)
+            .unwrap_or_else(|| moves[0].clone())
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        self.inner.choose_color(view)
+    }
+
+    fn on_event(&mut self, event: &GameEvent) {
+        self.inner.on_event(event)
+    }
+
+    fn on_game_end(&mut self, won: bool) {
+        self.inner.on_game_end(won)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        card::Card,
+        face::Face,
+        state::GameState,
+        variants::{Classic, Ruleset},
+    };
+
+    fn red(n: u8) -> Card {
+        Card::new_colored(Face::Number(n), ColorSuite::Red)
+    }
+
+    fn blue(n: u8) -> Card {
+        Card::new_colored(Face::Number(n), ColorSuite::Blue)
+    }
+
+    /// Seat 0 can go out with its last red card, seat 1 goes out right after
+    /// unless seat 0 does, and the deck only holds cards neither can play
+    fn race() -> GameState {
+        let rules = Classic.default_rules();
+        let mut state = GameState::new(
+            2,
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(1),
+        );
+        state.dealer.replace_cards(vec![blue(1), blue(2), blue(3)]);
+        state.hands[0].add_card(red(5));
+        state.hands[1].add_card(red(3));
+        state.table.add_target(red(7));
+        state.order.next();
+        state
+    }
+
+    #[test]
+    fn solves_a_race_to_the_last_card() {
+        let simulation = Simulation::new(&Classic, race(), 0);
+        let results = EndgameSolver::exact(10_000, 0).solve(&simulation).unwrap();
+        let chance = |wanted: &Action| {
+            results
+                .iter()
+                .find(|(action, _)| action == wanted)
+                .map(|&(_, chance)| chance)
+                .unwrap()
+        };
+        let play = Action::Play {
+            target: 0,
+            cards: vec![red(5)],
+            color: None,
+        };
+        assert_eq!(chance(&play), 1.0);
+        assert_eq!(chance(&Action::Draw), 0.0);
+    }
+
+    #[test]
+    fn weighs_every_card_the_deck_can_deal() {
+        let rules = Rules {
+            must_play: true,
+            play_drawn: true,
+            ..Classic.default_rules()
+        };
+        let mut state = GameState::new(
+            2,
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(1),
+        );
+        let green = Card::new_colored(Face::Number(4), ColorSuite::Green);
+        state
+            .dealer
+            .replace_cards(vec![blue(7), blue(7), red(2), green]);
+        state.hands[0].add_card(blue(5));
+        state.hands[1].add_card(red(3));
+        state.table.add_target(red(7));
+        state.order.next();
+
+        // Seat 0 has to draw, and only a blue 7 turns the color so that
+        // seat 1 can't go out and seat 0 goes out on the turn after
+        let simulation = Simulation::new(&Classic, state, 0);
+        let results = EndgameSolver::exact(10_000, 0).solve(&simulation).unwrap();
+        assert_eq!(results, [(Action::Draw, 0.5)]);
+    }
+
+    #[test]
+    fn only_sees_the_hidden_cards_when_the_game_allows_it() {
+        let state = race();
+        assert!(PlayerView::new(&Classic, &state, 0).reveal().is_none());
+        let view = PlayerView::new(&Classic, &state, 0).with_perfect_information(true);
+        assert_eq!(view.reveal().unwrap().hand_sizes(), [1, 1]);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    driver::TurnDriver,
+    encoding::{Encoding, Observation},
+    options::seat_seed,
+    rules::Rules,
+    setup::GameSetup,
+    state::GameState,
+    strategy::{Strategy, StrategyFactory},
+    variants::Ruleset,
+    view::PlayerView,
+};
+
+/// A reinforcement learning environment in the style of OpenAI Gym.
+/// A single agent plays against opponents driven by ordinary strategies,
+/// making one move for every call to `step`. The opponents' turns are played
+/// in between, so every observation is taken when the agent has a move to make
+/// or the game is over.
+///
+/// Observations and moves are numbered by an `Encoding` of the game.
+/// Moves without an index, such as Dos's plays of two cards, are left out of the legal action mask.
+///
+/// Should be constructed using `UnoEnvBuilder`
+pub struct UnoEnv {
+    ruleset: &'static dyn Ruleset,
+    rules: Rules,
+    opponents: Vec<StrategyFactory>,
+
+    /// The agent's seat
+    seat: usize,
+
+    encoding: Encoding,
+
+    /// The game in progress between `reset` and the end of the episode
+    episode: Option<TurnDriver>,
+}
+
+impl UnoEnv {
+    fn new(
+        ruleset: &'static dyn Ruleset,
+        rules: Rules,
+        opponents: Vec<StrategyFactory>,
+        seat: usize,
+    ) -> Self {
+        let encoding = Encoding::new(
+            &ruleset.cards(&rules),
+            ruleset.colors(&rules),
+            opponents.len() + 1,
+        );
+        Self {
+            ruleset,
+            rules,
+            opponents,
+            seat,
+            encoding,
+            episode: None,
+        }
+    }
+
+    /// The amount of players, including the agent
+    pub fn players(&self) -> usize {
+        self.opponents.len() + 1
+    }
+
+    /// The agent's seat
+    pub fn seat(&self) -> usize {
+        self.seat
+    }
+
+    /// How observations and moves are numbered
+    pub fn encoding(&self) -> &Encoding {
+        &self.encoding
+    }
+
+    /// The length of every observation
+    pub fn observation_size(&self) -> usize {
+        self.encoding.observation_size()
+    }
+
+    /// The amount of action indices
+    pub fn action_count(&self) -> usize {
+        self.encoding.action_count()
+    }
+
+    /// Starts a new game, dealt and shuffled the same way for the same `seed`,
+    /// and plays the opponents' turns until the agent has a move to make.
+    /// Returns the first observation.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        let players = self.players();
+        let state = GameState::new(
+            players,
+            self.rules,
+            self.ruleset.cards(&self.rules),
+            self.ruleset.colors(&self.rules),
+            Some(seed),
+        );
+        // The agent makes its own moves, so it has no strategy
+        let mut opponents = self.opponents.iter();
+        let strategies: Vec<Option<Box<dyn Strategy>>> = (0..players)
+            .map(|seat| {
+                (seat != self.seat).then(|| opponents.next().unwrap().build(seat_seed(seed, seat)))
+            })
+            .collect();
+        let names = (0..players).map(|seat| format!("seat {}", seat)).collect();
+        let mut driver = TurnDriver::new(self.ruleset, state, strategies, names);
+        driver.start();
+        driver.advance();
+        self.episode = Some(driver);
+        self.observation()
+    }
+
+    /// Makes the move numbered `action` for the agent and plays the opponents' turns
+    /// until the agent has another move to make or the game is over.
+    /// Returns the next observation, the reward and whether the game is over.
+    /// The reward is 1 when the agent wins, -1 when an opponent wins and 0 otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no game is in progress or the move is not legal
+    pub fn step(&mut self, action: usize) -> (Observation, f64, bool) {
+        let mask = self.legal_mask();
+        if !mask.get(action).copied().unwrap_or(false) {
+            panic!("the agent tried to make the illegal move {}", action);
+        }
+        let action = self.encoding.action(action).clone();
+        let episode = self.episode.as_mut().unwrap();
+        episode.apply(action);
+        episode.advance();
+
+        let reward = match self.winner() {
+            Some(winner) if winner == self.seat => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
+        };
+        (self.observation(), reward, self.is_done())
+    }
+
+    /// The seat that won, if the game is over
+    pub fn winner(&self) -> Option<usize> {
+        self.episode.as_ref().and_then(TurnDriver::winner)
+    }
+
+    /// Indicates whether the game is over, or none was started
+    pub fn is_done(&self) -> bool {
+        self.episode
+            .as_ref()
+            .is_none_or(|episode| episode.winner().is_some())
+    }
+
+    /// Returns whether every action index is a legal move for the agent,
+    /// which is all false while no game is in progress
+    pub fn legal_mask(&self) -> Vec<bool> {
+        let moves = match &self.episode {
+            Some(episode) if episode.winner().is_none() => {
+                self.ruleset.legal_moves(episode.state(), self.seat)
+            }
+            _ => Vec::new(),
+        };
+        self.encoding.legal_mask(&moves)
+    }
+
+    /// What the agent can see of the game in progress
+    ///
+    /// # Panics
+    ///
+    /// Panics if no game was started
+    pub fn view(&self) -> PlayerView<'_> {
+        let episode = self.episode.as_ref().expect("no game was started");
+        PlayerView::new(self.ruleset, episode.state(), self.seat)
+    }
+
+    /// Encodes what the agent can see, see `Encoding::encode`.
+    /// Returns all zeros while no game is in progress.
+    pub fn observation(&self) -> Observation {
+        match &self.episode {
+            Some(episode) => {
+                self.encoding
+                    .encode(&PlayerView::new(self.ruleset, episode.state(), self.seat))
+            }
+            None => vec![0.0; self.encoding.observation_size()],
+        }
+    }
+}
+
+/// A builder for the `UnoEnv`
+pub struct UnoEnvBuilder {
+    setup: GameSetup,
+    opponents: Vec<StrategyFactory>,
+    seat: usize,
+}
+
+impl UnoEnvBuilder {
+    pub fn new() -> Self {
+        Self {
+            setup: GameSetup::new(),
+            opponents: Vec::new(),
+            seat: 0,
+        }
+    }
+
+    /// Seats an opponent, in the first seat not taken by the agent or another opponent
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - A `StrategyFactory` that builds how the opponent plays in each game
+    pub fn add_opponent(mut self, strategy: StrategyFactory) -> Self {
+        self.opponents.push(strategy);
+        self
+    }
+
+    /// Sets the agent's seat, which is the first by default
+    ///
+    /// # Arguments
+    ///
+    /// * `seat` - The seat the agent plays in
+    pub fn with_seat(mut self, seat: usize) -> Self {
+        self.seat = seat;
+        self
+    }
+
+    /// Sets the game to play and the rules to play it by
+    ///
+    /// # Arguments
+    ///
+    /// * `setup` - A `GameSetup` naming the variant, rules and deck
+    pub fn with_setup(mut self, setup: GameSetup) -> Self {
+        self.setup = setup;
+        self
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there are no opponents or the agent's seat is not at the table
+    pub fn build(self) -> UnoEnv {
+        if self.opponents.is_empty() {
+            panic!("the environment needs at least one opponent");
+        }
+        if self.seat > self.opponents.len() {
+            panic!(
+                "seat {} is not at a table of {} players",
+                self.seat,
+                self.opponents.len() + 1
+            );
+        }
+        UnoEnv::new(
+            self.setup.ruleset(),
+            self.setup.rules(),
+            self.opponents,
+            self.seat,
+        )
+    }
+}
+
+impl Default for UnoEnvBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::with_colors;
+
+    /// The agent in the middle seat of a three player game against naive opponents
+    fn env() -> UnoEnv {
+        UnoEnvBuilder::new()
+            .add_opponent(StrategyFactory::naive())
+            .add_opponent(StrategyFactory::naive())
+            .with_seat(1)
+            .build()
+    }
+
+    /// Plays the episode dealt from `seed` by always making the first legal move,
+    /// calling `check` before every move and returning the last reward
+    fn play(env: &mut UnoEnv, seed: u64, mut check: impl FnMut(&UnoEnv)) -> f64 {
+        env.reset(seed);
+        for _ in 0..10_000 {
+            check(env);
+            let action = env.legal_mask().iter().position(|&legal| legal).unwrap();
+            let (_, reward, done) = env.step(action);
+            if done {
+                return reward;
+            }
+            assert_eq!(reward, 0.0);
+        }
+        panic!("the episode dealt from {} did not end", seed);
+    }
+
+    #[test]
+    fn an_episode_ends_with_a_reward_for_the_winner() {
+        let mut env = env();
+        assert!(env.is_done());
+        for seed in 0..5 {
+            let reward = play(&mut env, seed, |env| assert!(!env.is_done()));
+            assert!(env.is_done());
+            let won = env.winner() == Some(env.seat());
+            assert_eq!(reward, if won { 1.0 } else { -1.0 });
+            assert!(env.legal_mask().iter().all(|&legal| !legal));
+        }
+    }
+
+    #[test]
+    fn the_legal_mask_marks_exactly_the_legal_moves() {
+        let mut env = env();
+        for seed in 0..5 {
+            play(&mut env, seed, |env| {
+                let state = env.episode.as_ref().unwrap().state();
+                let moves = env.ruleset.legal_moves(state, env.seat);
+                let mut legal: Vec<usize> = with_colors(&moves, state.colors)
+                    .iter()
+                    .filter_map(|action| env.encoding().index_of(action))
+                    .collect();
+                legal.sort();
+                legal.dedup();
+                let marked: Vec<usize> = (0..env.action_count())
+                    .filter(|&index| env.legal_mask()[index])
+                    .collect();
+                assert_eq!(marked, legal);
+            });
+        }
+    }
+
+    #[test]
+    fn observations_have_the_encoded_size() {
+        let mut env = env();
+        assert_eq!(env.observation().len(), env.observation_size());
+        assert_eq!(env.reset(7).len(), env.observation_size());
+        play(&mut env, 7, |env| {
+            assert_eq!(env.observation().len(), env.observation_size())
+        });
+        assert_eq!(env.observation().len(), env.observation_size());
+    }
+}
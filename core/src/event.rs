@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::{card::Card, color::ColorSuite, player::Player, reversible_ring::Direction};
+
+/// Something that happened during a game.
+/// Events only carry public information, so every player may see all of them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum GameEvent {
+    /// `seat` was dealt their starting hand of `count` cards
+    Dealt { seat: usize, count: usize },
+
+    /// `card` was turned over from the deck onto the table
+    Flipped { card: Card },
+
+    /// `seat` played `cards` onto the target at index `target`
+    Played {
+        seat: usize,
+        target: usize,
+        cards: Vec<Card>,
+    },
+
+    /// `seat` added `card` to the center row
+    Placed { seat: usize, card: Card },
+
+    /// `seat` declared the color of a wild card
+    ColorChosen { seat: usize, color: ColorSuite },
+
+    /// `seat` drew `count` cards
+    Drew { seat: usize, count: usize },
+
+    /// `seat` ended their turn without playing
+    Passed { seat: usize },
+
+    /// `seat` reversed the direction of play
+    Reversed { seat: usize, direction: Direction },
+
+    /// `seat` loses their turn
+    Skipped { seat: usize },
+
+    /// `seat` took the whole plus stack of `count` cards
+    StackResolved { seat: usize, count: usize },
+
+    /// `seat` swapped hands with `other`
+    HandsSwapped { seat: usize, other: usize },
+
+    /// Every hand was passed along in `direction`
+    HandsRotated { direction: Direction },
+
+    /// The deck ran out and `cards` cards from the table were shuffled back in
+    Reshuffled { cards: usize },
+
+    /// `seat` won the game.
+    /// A blocked game is won by the smallest hand.
+    Won { seat: usize, blocked: bool },
+}
+
+/// Receives every event of a game as it happens
+pub trait Observer: Send {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+/// Builds a fresh `Observer` for every game.
+/// Observers that collect results across games can share state through the closure.
+pub type ObserverFactory = Arc<dyn Fn() -> Box<dyn Observer> + Send + Sync>;
+
+/// Prints the events of a game to stdout.
+/// Wins are always printed, everything else only when `verbose` is set.
+pub struct Printer {
+    players: Vec<Player>,
+    verbose: bool,
+}
+
+impl Printer {
+    pub fn new(players: Vec<Player>, verbose: bool) -> Self {
+        Self { players, verbose }
+    }
+
+    /// Returns a line describing `event`, naming players instead of seats
+    fn describe(&self, event: &GameEvent) -> String {
+        let p = |seat: &usize| &self.players[*seat];
+        match event {
+            GameEvent::Dealt { seat, count } => format!("{} was dealt {} cards", p(seat), count),
+            GameEvent::Flipped { card } => format!("{} was flipped onto the table", card),
+            GameEvent::Played { seat, cards, .. } => {
+                let cards: Vec<String> = cards.iter().map(Card::to_string).collect();
+                format!("{} played {}", p(seat), cards.join(" + "))
+            }
+            GameEvent::Placed { seat, card } => format!("{} added {} to the row", p(seat), card),
+            GameEvent::ColorChosen { seat, color } => format!("{} chose {}", p(seat), color),
+            GameEvent::Drew { seat, count: 1 } => format!("{} drew a card", p(seat)),
+            GameEvent::Drew { seat, count } => format!("{} drew {} cards", p(seat), count),
+            GameEvent::Passed { seat } => format!("{} passed", p(seat)),
+            GameEvent::Reversed { seat, .. } => format!("{} reversed the order", p(seat)),
+            GameEvent::Skipped { seat } => format!("{} was skipped", p(seat)),
+            GameEvent::StackResolved { seat, count } => {
+                format!("{} took a stack of {}", p(seat), count)
+            }
+            GameEvent::HandsSwapped { seat, other } => {
+                format!("{} swapped hands with {}", p(seat), p(other))
+            }
+            GameEvent::HandsRotated { .. } => "Every hand was passed along".to_owned(),
+            GameEvent::Reshuffled { .. } => "Deck Empty: Reshuffling...".to_owned(),
+            GameEvent::Won { seat, .. } => format!(
+                "{} has won using the {} strategy",
+                p(seat).name,
+                p(seat).strategy_name()
+            ),
+        }
+    }
+}
+
+impl Observer for Printer {
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::Won { blocked: true, .. } => {
+                println!("The game is blocked");
+                println!("{}", self.describe(event))
+            }
+            GameEvent::Won { .. } | GameEvent::Reshuffled { .. } => {
+                println!("{}", self.describe(event))
+            }
+            _ if self.verbose => println!("{}", self.describe(event)),
+            _ => {}
+        }
+    }
+}
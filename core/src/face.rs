@@ -1,17 +1,12 @@
 use std::fmt;
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash)]
 pub enum Face {
-    Zero,
-    One,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
+    /// A numeric card. Standard decks print 0 through 9,
+    /// but a `DeckSpec` can widen or narrow the range.
+    Number(u8),
+    /// A playing card rank from Ace (1) to King (13)
+    Rank(u8),
     Skip,
     Reverse,
     PlusTwo,
@@ -21,45 +16,25 @@ pub enum Face {
 
 impl Face {
     pub fn is_plus(&self) -> bool {
-        match self {
-            Face::PlusTwo | Face::PlusFour => true,
-            _ => false,
-        }
+        matches!(self, Face::PlusTwo | Face::PlusFour)
     }
 
-    /// Returns all faces that appear twice for each color
-    /// in a standard Uno deck
-    pub fn double_faces() -> [Self; 12] {
-        [
-            Face::One,
-            Face::Two,
-            Face::Three,
-            Face::Four,
-            Face::Five,
-            Face::Six,
-            Face::Seven,
-            Face::Eight,
-            Face::Nine,
-            Face::Skip,
-            Face::Reverse,
-            Face::PlusTwo,
-        ]
+    /// Returns the colored action faces.
+    /// These appear twice for each color in a standard Uno deck.
+    pub fn actions() -> [Self; 3] {
+        [Face::Skip, Face::Reverse, Face::PlusTwo]
     }
 }
 
 impl fmt::Display for Face {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let res = match self {
-            Self::Zero => "0",
-            Self::One => "1",
-            Self::Two => "2",
-            Self::Three => "3",
-            Self::Four => "4",
-            Self::Five => "5",
-            Self::Six => "6",
-            Self::Seven => "7",
-            Self::Eight => "8",
-            Self::Nine => "9",
+            Self::Number(n) => return write!(f, "{}", n),
+            Self::Rank(1) => "A",
+            Self::Rank(11) => "J",
+            Self::Rank(12) => "Q",
+            Self::Rank(13) => "K",
+            Self::Rank(n) => return write!(f, "{}", n),
             Self::Skip => "🛇",
             Self::Reverse => "⤤ ⤦ ",
             Self::Wild => "Wild",
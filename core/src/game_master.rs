@@ -1,20 +1,30 @@
-use std::iter;
 use std::sync::{Arc, Mutex};
-use std::{collections::VecDeque, thread};
+use std::thread;
 
+use crate::event::{Observer, ObserverFactory};
 use crate::options::Options;
-use crate::{game::Game, player::Player, rules::Rules, strategy::Strategy};
+use crate::setup::GameSetup;
+use crate::variants::Ruleset;
+use crate::{game::Game, player::Player, rules::Rules, strategy::StrategyFactory};
 
 #[derive(Clone)]
 struct GameDaemon {
-    players: VecDeque<Player>,
+    players: Vec<Player>,
+    ruleset: &'static dyn Ruleset,
     rules: Rules,
-    verbose: bool,
+    options: Options,
+    observers: Vec<ObserverFactory>,
 }
 
 impl GameDaemon {
-    fn run(&self) -> Player {
-        Game::new(self.players.clone(), self.rules, self.verbose).play_until_winner()
+    /// Plays game `game` of the run
+    fn run(&self, game: usize) -> Player {
+        let options = self.options.game_options(game);
+        let mut game = Game::new(self.players.clone(), self.ruleset, self.rules, options);
+        for build in self.observers.iter() {
+            game.add_observer(build());
+        }
+        game.play_until_winner()
     }
 }
 
@@ -23,6 +33,11 @@ pub struct ScoreBoard {
 }
 
 impl ScoreBoard {
+    /// Returns how many games every player won, indexed by seat
+    pub fn wins(&self) -> Vec<u32> {
+        self.scores.lock().unwrap().clone()
+    }
+
     pub fn display_results(&self) {
         let scores = self.scores.clone();
         let scores = scores.lock().unwrap();
@@ -36,25 +51,36 @@ impl ScoreBoard {
 /// Should be constructed using `GameMasterBuilder`
 pub struct GameMaster {
     players: Vec<Player>,
+    ruleset: &'static dyn Ruleset,
     rules: Rules,
     options: Options,
+    observers: Vec<ObserverFactory>,
 }
 
 impl GameMaster {
-    fn new(players: Vec<Player>, rules: Rules, options: Options) -> Self {
+    fn new(
+        players: Vec<Player>,
+        ruleset: &'static dyn Ruleset,
+        rules: Rules,
+        options: Options,
+        observers: Vec<ObserverFactory>,
+    ) -> Self {
         Self {
             players,
+            ruleset,
             rules,
             options,
+            observers,
         }
     }
 
     fn spawn_daemon(&self) -> GameDaemon {
-        let players = VecDeque::from(self.players.clone());
         GameDaemon {
-            players: VecDeque::from(players),
+            players: self.players.clone(),
+            ruleset: self.ruleset,
             rules: self.rules,
-            verbose: self.options.verbose,
+            options: self.options,
+            observers: self.observers.clone(),
         }
     }
 
@@ -67,7 +93,7 @@ impl GameMaster {
         let mut handles = Vec::with_capacity(self.options.num_threads);
         let num_threads = self.options.num_threads;
 
-        let mut counters: Vec<u32> = iter::repeat(0).take(self.players.len()).collect();
+        let counters: Vec<u32> = vec![0; self.players.len()];
         let shared_counters = Arc::new(Mutex::new(counters));
 
         for i in 0..num_threads {
@@ -77,8 +103,8 @@ impl GameMaster {
             let handle = thread::spawn(move || {
                 let start = i * n / num_threads;
                 let end = (i + 1) * n / num_threads;
-                for _ in start..end {
-                    let winner = daemon.run();
+                for game in start..end {
+                    let winner = daemon.run(game);
 
                     let mut counters = shared_counters.lock().unwrap();
                     counters[winner.id as usize] += 1;
@@ -99,8 +125,9 @@ impl GameMaster {
 /// A builder for the `GameMaster`
 pub struct GameMasterBuilder {
     players: Vec<Player>,
-    rules: Rules,
+    setup: GameSetup,
     options: Options,
+    observers: Vec<ObserverFactory>,
 
     last_id: u32,
 }
@@ -109,13 +136,14 @@ impl GameMasterBuilder {
     pub fn new() -> Self {
         Self {
             players: Vec::new(),
-            rules: Rules::default(),
+            setup: GameSetup::new(),
             options: Options::default(),
+            observers: Vec::new(),
             last_id: 0,
         }
     }
 
-    fn new_player(&mut self, name: String, strategy: Strategy) -> Player {
+    fn new_player(&mut self, name: String, strategy: StrategyFactory) -> Player {
         let player = Player::new(self.last_id, name, strategy);
         self.last_id += 1;
         player
@@ -126,13 +154,28 @@ impl GameMasterBuilder {
     /// # Arguments
     ///
     /// * `name` - A `String` that represents the name of the player
-    /// * `strategy` A `Strategy` struct that represents how the player plays
-    pub fn add_player(mut self, name: String, strategy: Strategy) -> Self {
+    /// * `strategy` A `StrategyFactory` that builds how the player plays in each game
+    pub fn add_player(mut self, name: String, strategy: StrategyFactory) -> Self {
         let player = self.new_player(name, strategy);
         self.players.push(player);
         self
     }
 
+    /// Registers an observer for every game.
+    /// `build` is called once per game, so observers that collect results
+    /// across games should share state through what the closure captures.
+    ///
+    /// # Arguments
+    ///
+    /// * `build` - A closure returning a new `Observer`
+    pub fn add_observer<F>(mut self, build: F) -> Self
+    where
+        F: Fn() -> Box<dyn Observer> + Send + Sync + 'static,
+    {
+        self.observers.push(Arc::new(build));
+        self
+    }
+
     /// Set the options to be used
     ///
     /// # Arguments
@@ -143,17 +186,89 @@ impl GameMasterBuilder {
         self
     }
 
-    /// Sets the rules to be used during the games of Uno
+    /// Sets the game to play and the rules to play it by
     ///
     /// # Arguments
     ///
-    /// * `rules` - A `Rules` struct
-    pub fn with_rules(mut self, rules: Rules) -> Self {
-        self.rules = rules;
+    /// * `setup` - A `GameSetup` naming the variant, rules and deck
+    pub fn with_setup(mut self, setup: GameSetup) -> Self {
+        self.setup = setup;
         self
     }
 
     pub fn build(self) -> GameMaster {
-        GameMaster::new(self.players, self.rules, self.options)
+        GameMaster::new(
+            self.players,
+            self.setup.ruleset(),
+            self.setup.rules(),
+            self.options,
+            self.observers,
+        )
+    }
+}
+
+impl Default for GameMasterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::GameEvent, registry::StrategyRegistry};
+
+    /// Records every event of a game, handing them over to `games` once the game is won
+    struct Recorder {
+        events: Vec<String>,
+        games: Arc<Mutex<Vec<Vec<String>>>>,
+    }
+
+    impl Observer for Recorder {
+        fn on_event(&mut self, event: &GameEvent) {
+            self.events.push(format!("{:?}", event));
+            if let GameEvent::Won { .. } = event {
+                let events = std::mem::take(&mut self.events);
+                self.games.lock().unwrap().push(events);
+            }
+        }
+    }
+
+    /// Plays a seeded run on several threads and returns the events of every game, sorted
+    fn seeded_run(seed: u64) -> Vec<Vec<String>> {
+        let registry = StrategyRegistry::new();
+        let strategy = |name: &str, value: &str| {
+            let params = [(name.to_owned(), value.to_owned())];
+            match name {
+                "rollouts" => registry.build("monte-carlo", &params),
+                _ => registry.build("ismcts", &params),
+            }
+            .unwrap()
+        };
+        let games = Arc::new(Mutex::new(Vec::new()));
+        let recorded = games.clone();
+        GameMasterBuilder::new()
+            .add_player("Monte".to_owned(), strategy("rollouts", "2"))
+            .add_player("Tree".to_owned(), strategy("iterations", "8"))
+            .add_player("Carlo".to_owned(), strategy("rollouts", "1"))
+            .add_observer(move || {
+                Box::new(Recorder {
+                    events: Vec::new(),
+                    games: recorded.clone(),
+                })
+            })
+            .with_options(Options::default().with_seed(seed).quiet())
+            .build()
+            .run(4);
+        let mut games = games.lock().unwrap().clone();
+        games.sort();
+        games
+    }
+
+    #[test]
+    fn seeded_runs_play_out_the_same_on_any_thread() {
+        let games = seeded_run(21);
+        assert_eq!(games.len(), 4);
+        assert_eq!(games, seeded_run(21));
     }
 }
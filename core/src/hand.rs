@@ -6,7 +6,7 @@ use std::{
 
 use crate::{card::Card, rules::PlusStacking};
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
 pub struct Hand {
     cards: Vec<Card>,
 }
@@ -22,14 +22,16 @@ impl Hand {
         self.cards
             .iter()
             .enumerate()
-            .filter_map(|(i, card)| card.stacks_on(target).then(|| (i)))
+            .filter(|(_, card)| card.stacks_on(target))
+            .map(|(i, _)| i)
             .collect()
     }
 
     /// Returns every index of a card that plus stacks on the `target` card
     pub fn all_plus_stackable(&self, target: &Card, rules: &PlusStacking) -> Vec<usize> {
         self.enumerate()
-            .filter_map(|(i, card)| card.plus_stacks_on(target, &rules).then(|| i))
+            .filter(|(_, card)| card.plus_stacks_on(target, rules))
+            .map(|(i, _)| i)
             .collect()
     }
 
@@ -47,6 +49,11 @@ impl Hand {
         self.cards.append(&mut cards)
     }
 
+    /// Returns the amount of cards in this hand
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
     /// Indicates whether the current hand has no cards
     pub fn is_empty(&self) -> bool {
         self.cards.is_empty()
@@ -57,6 +64,13 @@ impl Hand {
         self.cards.swap_remove(i)
     }
 
+    /// Removes a card equal to `card` and returns it,
+    /// or `None` if the hand holds no such card
+    pub fn remove_card(&mut self, card: &Card) -> Option<Card> {
+        let i = self.cards.iter().position(|held| held == card)?;
+        Some(self.remove(i))
+    }
+
     /// Returns a reference to the card at the given index
     pub fn get(&self, i: usize) -> &Card {
         &self.cards[i]
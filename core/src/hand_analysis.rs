@@ -1,36 +1,60 @@
-use crate::{color::Color, hand::Hand};
-/*
+use std::collections::BTreeMap;
+
+use crate::{card::Card, color::ColorSuite, face::Face, hand::Hand};
+
+/// Counts the cards of a hand by color and by face
+#[derive(Clone, Debug, Default)]
 pub struct CardHistogram {
-    hist: [u32; 4],
+    colors: BTreeMap<ColorSuite, usize>,
+    faces: BTreeMap<Face, usize>,
+    wilds: usize,
 }
 
 impl CardHistogram {
-    fn new(hand: &Hand) -> Self {
-        let mut hist = [0; 4];
+    pub fn new(hand: &Hand) -> Self {
+        let mut hist = Self::default();
         for card in hand {
-            let i = card.color.to_num();
-            hist[i as usize] += 1;
+            if card.is_wild() {
+                hist.wilds += 1;
+            } else if let Some(color) = card.color_suite() {
+                *hist.colors.entry(color).or_insert(0) += 1;
+            }
+            *hist.faces.entry(card.face).or_insert(0) += 1;
         }
-        Self { hist }
+        hist
+    }
+
+    /// Returns the amount of colored cards of `color`. Wild cards never count.
+    pub fn color(&self, color: ColorSuite) -> usize {
+        self.colors.get(&color).copied().unwrap_or(0)
+    }
+
+    /// Returns the amount of cards with `face`
+    pub fn face(&self, face: Face) -> usize {
+        self.faces.get(&face).copied().unwrap_or(0)
+    }
+
+    /// Returns the amount of wild cards
+    pub fn wilds(&self) -> usize {
+        self.wilds
     }
 
-    /// Returns the color with the max amount of occurences in this histogram
-    pub fn max(&self) -> (Color, u32) {
-        let (i, val) = self
-            .hist
+    /// Returns the color with the max amount of occurences in this histogram,
+    /// or `None` if the hand holds no colored cards
+    pub fn max(&self) -> Option<(ColorSuite, usize)> {
+        self.colors
             .iter()
-            .enumerate()
-            .max_by_key(|&(_, val)| val)
-            .unwrap();
-        (Color::from_num(self.hist[i]), *val)
+            .rev()
+            .max_by_key(|&(_, count)| count)
+            .map(|(&color, &count)| (color, count))
     }
 }
 
+/// Answers questions about a hand, building what it needs on first use
 pub struct HandAnalysis<'a> {
     hand: &'a Hand,
 
     histogram: Option<CardHistogram>,
-    histogram_built: bool,
 }
 
 impl<'a> HandAnalysis<'a> {
@@ -38,17 +62,38 @@ impl<'a> HandAnalysis<'a> {
         Self {
             hand,
             histogram: None,
-            histogram_built: false,
         }
     }
 
     /// Returns a histogram from this HandAnalysis
     pub fn histogram(&mut self) -> &CardHistogram {
-        if self.histogram.is_none() {
-            let hist = CardHistogram::new(self.hand);
-            self.histogram = Some(hist);
+        self.histogram
+            .get_or_insert_with(|| CardHistogram::new(self.hand))
+    }
+
+    /// Returns the amount of cards that could be played on `target`
+    pub fn playable_on(&self, target: &Card) -> usize {
+        self.hand
+            .into_iter()
+            .filter(|card| card.stacks_on(target))
+            .count()
+    }
+
+    /// Returns every action and wild card in the hand
+    pub fn specials(&self) -> Vec<Card> {
+        self.hand
+            .into_iter()
+            .filter(|card| card.is_special())
+            .copied()
+            .collect()
+    }
+
+    /// Returns the amount of special cards of each face
+    pub fn special_counts(&self) -> BTreeMap<Face, usize> {
+        let mut counts = BTreeMap::new();
+        for card in self.specials() {
+            *counts.entry(card.face).or_insert(0) += 1;
         }
-        self.histogram.as_ref().unwrap()
+        counts
     }
 }
-*/
@@ -0,0 +1,324 @@
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    action::Action, algorithms::Counting, card::Card, color::ColorSuite, face::Face,
+    hand_analysis::CardHistogram, search::with_colors, strategy::Strategy, view::PlayerView,
+};
+
+/// The amount of features a `Heuristic` weighs
+pub const FEATURE_COUNT: usize = 5;
+
+/// The name of every feature, in the order of the weights
+pub const FEATURES: [&str; FEATURE_COUNT] = [
+    "same_color",
+    "action_value",
+    "next_hand",
+    "wilds_kept",
+    "draw",
+];
+
+/// Weights that play reasonably before any tuning
+pub const DEFAULT_WEIGHTS: [f64; FEATURE_COUNT] = [1.0, 0.5, -2.0, 1.0, -5.0];
+
+/// Scores every candidate move by a weighted sum of features and makes the best one.
+/// Wild cards are scored once for every color they can be declared as.
+///
+/// The features of a move are, in order:
+///
+/// * `same_color` - Cards left in the hand of the color on top after the move
+/// * `action_value` - What the cards played do to the others: 1 for a skip or reverse,
+///   2 for a plus two and 4 for a plus four
+/// * `next_hand` - How close the player moving next is to going out, as one over their hand size
+/// * `wilds_kept` - Wild cards left in the hand after the move, which keep it flexible
+/// * `draw` - 1 for a move that plays no cards
+pub struct Heuristic {
+    weights: [f64; FEATURE_COUNT],
+}
+
+impl Heuristic {
+    /// # Arguments
+    ///
+    /// * `weights` - The weight of every feature, in the order of `FEATURES`
+    pub fn new(weights: [f64; FEATURE_COUNT]) -> Self {
+        Self { weights }
+    }
+
+    /// Returns the features of `action`, in the order of `FEATURES`
+    pub fn features(action: &Action, view: &PlayerView) -> [f64; FEATURE_COUNT] {
+        let played = action.cards();
+        let mut rest = view.hand().clone();
+        for card in played {
+            rest.remove_card(card);
+        }
+        let declared = match action {
+            Action::Play { color, .. } | Action::Place { color, .. } => *color,
+            _ => None,
+        };
+        let top = match (played.last(), declared) {
+            (Some(&card), Some(color)) if card.is_wild() => Card::new_colored(card.face, color),
+            (Some(&card), _) => card,
+            (None, _) => *view.top(),
+        };
+
+        let same_color = top.color_suite().map_or(0, |color| {
+            (&rest)
+                .into_iter()
+                .filter(|card| !card.is_wild() && card.color_suite() == Some(color))
+                .count()
+        });
+        let action_value: usize = played
+            .iter()
+            .map(|card| match card.face {
+                Face::Skip | Face::Reverse => 1,
+                Face::PlusTwo => 2,
+                Face::PlusFour => 4,
+                _ => 0,
+            })
+            .sum();
+        let next = match played.last() {
+            Some(card) => Counting::follower(card, view),
+            None => view.next_seat(),
+        };
+        let next_hand = if next == view.seat() {
+            0.0
+        } else {
+            1.0 / view.hand_sizes()[next].max(1) as f64
+        };
+        let wilds_kept = (&rest).into_iter().filter(|card| card.is_wild()).count();
+        let draw = if action.is_play() { 0.0 } else { 1.0 };
+        [
+            same_color as f64,
+            action_value as f64,
+            next_hand,
+            wilds_kept as f64,
+            draw,
+        ]
+    }
+
+    /// Returns the weighted sum of the features of `action`
+    pub fn score(&self, action: &Action, view: &PlayerView) -> f64 {
+        Self::features(action, view)
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(feature, weight)| feature * weight)
+            .sum()
+    }
+}
+
+impl Default for Heuristic {
+    fn default() -> Self {
+        Self::new(DEFAULT_WEIGHTS)
+    }
+}
+
+impl Strategy for Heuristic {
+    fn name(&self) -> &str {
+        "Heuristic"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        // Ties go to the move listed first
+        with_colors(moves, view.colors())
+            .into_iter()
+            .map(|action| (self.score(&action, view), action))
+            .rev()
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, action)| action)
+            .unwrap()
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        CardHistogram::new(view.hand())
+            .max()
+            .map(|(color, _)| color)
+    }
+}
+
+/// Tunes the weights of a `Heuristic` with a genetic algorithm.
+/// Every generation keeps its best candidates as they are and breeds the rest
+/// from the better half by uniform crossover and gaussian mutation.
+pub struct GeneticTuner {
+    /// The amount of candidates in every generation
+    population: usize,
+
+    /// The amount of best candidates carried over to the next generation unchanged
+    elite: usize,
+
+    /// The standard deviation of the noise added to every weight of a child
+    mutation: f64,
+
+    rng: SmallRng,
+}
+
+impl GeneticTuner {
+    /// # Arguments
+    ///
+    /// * `population` - The amount of candidates in every generation
+    /// * `elite` - The amount of best candidates carried over unchanged
+    /// * `mutation` - The standard deviation of the noise added to every weight of a child
+    /// * `seed` - Seeds the breeding
+    pub fn new(population: usize, elite: usize, mutation: f64, seed: u64) -> Self {
+        let population = population.max(2);
+        Self {
+            population,
+            elite: elite.clamp(1, population - 1),
+            mutation,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns a sample of the standard normal distribution, by the Box-Muller transform
+    fn gaussian(&mut self) -> f64 {
+        let u: f64 = 1.0 - self.rng.gen::<f64>();
+        let v: f64 = self.rng.gen();
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+
+    fn mutate(&mut self, weights: &mut [f64; FEATURE_COUNT]) {
+        for weight in weights.iter_mut() {
+            *weight += self.mutation * self.gaussian();
+        }
+    }
+
+    /// Evolves the weights for `generations` generations, starting around `start`,
+    /// and returns the best weights of the last generation along with their fitness.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The weights the first generation is spread around
+    /// * `generations` - How many generations to evolve
+    /// * `fitness` - Rates a candidate, higher is better, given the generation it is part of
+    /// * `on_generation` - Called with the generation and its best candidate and fitness
+    pub fn run<F, G>(
+        &mut self,
+        start: [f64; FEATURE_COUNT],
+        generations: usize,
+        mut fitness: F,
+        mut on_generation: G,
+    ) -> ([f64; FEATURE_COUNT], f64)
+    where
+        F: FnMut(usize, &[f64; FEATURE_COUNT]) -> f64,
+        G: FnMut(usize, &[f64; FEATURE_COUNT], f64),
+    {
+        let mut candidates = vec![start];
+        while candidates.len() < self.population {
+            let mut weights = start;
+            self.mutate(&mut weights);
+            candidates.push(weights);
+        }
+
+        let mut best = (start, f64::NEG_INFINITY);
+        for generation in 0..generations.max(1) {
+            let mut rated: Vec<([f64; FEATURE_COUNT], f64)> = candidates
+                .iter()
+                .map(|weights| (*weights, fitness(generation, weights)))
+                .collect();
+            rated.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            best = rated[0];
+            on_generation(generation, &best.0, best.1);
+
+            let parents: Vec<[f64; FEATURE_COUNT]> = rated
+                .iter()
+                .take((self.population / 2).max(2))
+                .map(|(weights, _)| *weights)
+                .collect();
+            candidates = rated
+                .iter()
+                .take(self.elite)
+                .map(|(weights, _)| *weights)
+                .collect();
+            while candidates.len() < self.population {
+                let mother = *parents.choose(&mut self.rng).unwrap();
+                let father = *parents.choose(&mut self.rng).unwrap();
+                let mut child = mother;
+                for (weight, other) in child.iter_mut().zip(father) {
+                    if self.rng.gen_bool(0.5) {
+                        *weight = other;
+                    }
+                }
+                self.mutate(&mut child);
+                candidates.push(child);
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::GameState,
+        variants::{Classic, Ruleset},
+    };
+
+    fn red(face: Face) -> Card {
+        Card::new_colored(face, ColorSuite::Red)
+    }
+
+    #[test]
+    fn extracts_the_features_of_a_play_and_a_draw() {
+        let rules = Classic.default_rules();
+        let mut state = GameState::new(
+            2,
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(6),
+        );
+        let plus_two = red(Face::PlusTwo);
+        state.hands[0].add_many(vec![
+            red(Face::Number(3)),
+            red(Face::Number(5)),
+            plus_two,
+            Card::new_wild(Face::Wild),
+        ]);
+        state.hands[1].add_many((1..=4).map(|n| red(Face::Number(n))).collect());
+        state.table.add_target(red(Face::Number(7)));
+        state.order.next();
+        let view = PlayerView::new(&Classic, &state, 0);
+
+        assert_eq!(
+            Heuristic::features(&Action::play(plus_two), &view),
+            [2.0, 2.0, 0.25, 1.0, 0.0]
+        );
+        assert_eq!(
+            Heuristic::features(&Action::Draw, &view),
+            [3.0, 0.0, 0.25, 1.0, 1.0]
+        );
+    }
+
+    /// Runs two generations towards fixed weights and returns every candidate rated,
+    /// by generation
+    fn evolve(seed: u64) -> Vec<Vec<([f64; FEATURE_COUNT], f64)>> {
+        let target = [2.0, -1.0, 0.5, 0.0, -3.0];
+        let mut rated = vec![Vec::new(), Vec::new()];
+        GeneticTuner::new(6, 1, 0.5, seed).run(
+            DEFAULT_WEIGHTS,
+            2,
+            |generation, weights| {
+                let distance: f64 = weights
+                    .iter()
+                    .zip(target)
+                    .map(|(weight, target)| (weight - target).powi(2))
+                    .sum();
+                rated[generation].push((*weights, -distance));
+                -distance
+            },
+            |_, _, _| {},
+        );
+        rated
+    }
+
+    #[test]
+    fn a_seeded_generation_breeds_the_same_and_keeps_its_best() {
+        let rated = evolve(9);
+        assert_eq!(rated, evolve(9));
+
+        let best = rated[0]
+            .iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+        assert!(rated[1].contains(best));
+    }
+}
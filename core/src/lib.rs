@@ -1,18 +1,71 @@
+//! Simulates games of Uno and its relatives between competing strategies.
+//!
+//! Write a bot by implementing [`Strategy`], wrap it in a [`StrategyFactory`]
+//! and seat it at a [`GameMasterBuilder`]. Everything needed for that is in the [`prelude`].
+
+mod action;
 mod algorithms;
 mod card;
+mod card_counter;
+#[allow(dead_code)]
 mod card_sequence;
 mod color;
+mod color_chooser;
 mod decision_tree;
 mod deck;
+mod driver;
+mod encoding;
+mod endgame;
+mod env;
+mod event;
 mod face;
 mod game;
+mod game_master;
 mod hand;
 mod hand_analysis;
+mod heuristic;
+mod mlp;
+mod opponent_model;
+mod options;
 mod pile;
 mod player;
+mod qlearning;
+mod registry;
 mod reversible_ring;
 mod rules;
+mod script;
+mod search;
+mod setup;
+mod simulation;
+mod state;
+mod strategy;
+mod table;
+mod view;
+
+pub mod prelude;
+pub mod variants;
 
-pub mod game_master;
-pub mod options;
-pub mod strategy;
+pub use action::Action;
+pub use card::Card;
+pub use color::{Color, ColorSuite};
+pub use color_chooser::{ColorChooser, ColorChooserFactory};
+pub use deck::{DeckError, DeckSpec};
+pub use encoding::{Encoding, Observation};
+pub use endgame::EndgameSolver;
+pub use env::{UnoEnv, UnoEnvBuilder};
+pub use event::{GameEvent, Observer, ObserverFactory};
+pub use face::Face;
+pub use game_master::{GameMaster, GameMasterBuilder, ScoreBoard};
+pub use hand::Hand;
+pub use hand_analysis::{CardHistogram, HandAnalysis};
+pub use heuristic::{GeneticTuner, Heuristic, DEFAULT_WEIGHTS, FEATURES, FEATURE_COUNT};
+pub use opponent_model::{CardKind, OpponentModel};
+pub use options::Options;
+pub use qlearning::{LearningRates, PolicyError, QTable, QTrainer};
+pub use registry::{ParamKind, ParamSpec, Params, StrategyEntry, StrategyError, StrategyRegistry};
+pub use reversible_ring::Direction;
+pub use rules::{PlusStacking, Rules, RulesBuilder, RulesError};
+pub use setup::GameSetup;
+pub use simulation::Simulation;
+pub use strategy::{Strategy, StrategyFactory};
+pub use view::PlayerView;
@@ -0,0 +1,384 @@
+use std::{fs, path::Path, sync::Arc};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    action::Action, color::ColorSuite, encoding::Encoding, hand_analysis::CardHistogram,
+    qlearning::PolicyError, search::rollout_move, strategy::Strategy, view::PlayerView,
+};
+
+/// The first line of every weights file
+const HEADER: &str = "# mlp v1";
+
+/// What a layer applies to its weighted sums
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Activation {
+    Relu,
+    Linear,
+}
+
+/// A fully connected layer
+#[derive(Clone, Debug)]
+pub struct Dense {
+    inputs: usize,
+    outputs: usize,
+
+    /// The weights of every output in turn, `inputs` at a time
+    weights: Vec<f32>,
+    biases: Vec<f32>,
+    activation: Activation,
+}
+
+impl Dense {
+    /// # Arguments
+    ///
+    /// * `weights` - The weights of every output, one row of inputs per output
+    /// * `biases` - The bias of every output
+    /// * `activation` - What is applied to the weighted sums
+    ///
+    /// # Panics
+    ///
+    /// Panics unless there is a bias for every row and every row is as long
+    pub fn new(weights: Vec<Vec<f32>>, biases: Vec<f32>, activation: Activation) -> Self {
+        let inputs = weights.first().map_or(0, Vec::len);
+        if weights.len() != biases.len() || weights.iter().any(|row| row.len() != inputs) {
+            panic!("every output of a layer needs a bias and as many weights as there are inputs");
+        }
+        Self {
+            inputs,
+            outputs: biases.len(),
+            weights: weights.concat(),
+            biases,
+            activation,
+        }
+    }
+
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.weights
+            .chunks_exact(self.inputs)
+            .zip(self.biases.iter())
+            .map(|(row, bias)| {
+                let sum = row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>() + bias;
+                match self.activation {
+                    Activation::Relu => sum.max(0.0),
+                    Activation::Linear => sum,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A multilayer perceptron, run on the CPU
+#[derive(Clone, Debug)]
+pub struct Mlp {
+    layers: Vec<Dense>,
+}
+
+impl Mlp {
+    /// # Panics
+    ///
+    /// Panics if there are no layers or a layer does not take as many inputs
+    /// as the layer before it outputs
+    pub fn new(layers: Vec<Dense>) -> Self {
+        if layers.is_empty() {
+            panic!("a network needs at least one layer");
+        }
+        for pair in layers.windows(2) {
+            if pair[0].outputs != pair[1].inputs {
+                panic!(
+                    "a layer of {} outputs is followed by a layer of {} inputs",
+                    pair[0].outputs, pair[1].inputs
+                );
+            }
+        }
+        Self { layers }
+    }
+
+    /// Reads a network from a weights file.
+    ///
+    /// The first line is `# mlp v1`. Every layer then starts with a line
+    /// `dense <inputs> <outputs> <relu|linear>`, followed by one line for every output
+    /// holding its `inputs` weights and then its bias, separated by whitespace.
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PolicyError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Reads a network in the format described by `load`
+    pub fn parse(text: &str) -> Result<Self, PolicyError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(PolicyError::Parse(1, format!("expected {}", HEADER)));
+        }
+        let mut lines = lines.filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let mut layers: Vec<Dense> = Vec::new();
+        while let Some((number, line)) = lines.next() {
+            let error = |reason: String| PolicyError::Parse(number, reason);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [kind, inputs, outputs, activation] = fields[..] else {
+                return Err(error(format!("expected a layer, got {}", line)));
+            };
+            if kind != "dense" {
+                return Err(error(format!("unknown layer {}", kind)));
+            }
+            let size = |field: &str| {
+                field
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&size| size > 0)
+                    .ok_or_else(|| error(format!("bad layer size {}", field)))
+            };
+            let (inputs, outputs) = (size(inputs)?, size(outputs)?);
+            let activation = match activation {
+                "relu" => Activation::Relu,
+                "linear" => Activation::Linear,
+                _ => return Err(error(format!("unknown activation {}", activation))),
+            };
+            if let Some(last) = layers.last() {
+                if last.outputs != inputs {
+                    return Err(error(format!(
+                        "the layer before outputs {}, not {}",
+                        last.outputs, inputs
+                    )));
+                }
+            }
+
+            let mut weights = Vec::with_capacity(outputs);
+            let mut biases = Vec::with_capacity(outputs);
+            for _ in 0..outputs {
+                let (number, line) = lines
+                    .next()
+                    .ok_or_else(|| PolicyError::Parse(number, "the layer ends early".to_owned()))?;
+                let mut row = line
+                    .split_whitespace()
+                    .map(str::parse::<f32>)
+                    .collect::<Result<Vec<f32>, _>>()
+                    .map_err(|err| PolicyError::Parse(number, format!("bad weight: {}", err)))?;
+                if row.len() != inputs + 1 {
+                    return Err(PolicyError::Parse(
+                        number,
+                        format!("expected {} weights and a bias, got {}", inputs, row.len()),
+                    ));
+                }
+                biases.push(row.pop().unwrap());
+                weights.push(row);
+            }
+            layers.push(Dense::new(weights, biases, activation));
+        }
+        if layers.is_empty() {
+            return Err(PolicyError::Parse(1, "no layers".to_owned()));
+        }
+        Ok(Self::new(layers))
+    }
+
+    /// The amount of inputs the network takes
+    pub fn inputs(&self) -> usize {
+        self.layers[0].inputs
+    }
+
+    /// The amount of outputs the network gives
+    pub fn outputs(&self) -> usize {
+        self.layers[self.layers.len() - 1].outputs
+    }
+
+    /// Runs the network on `input`
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `input` holds `inputs` numbers
+    pub fn forward(&self, input: &[f32]) -> Vec<f32> {
+        if input.len() != self.inputs() {
+            panic!(
+                "the network takes {} inputs, got {}",
+                self.inputs(),
+                input.len()
+            );
+        }
+        self.layers
+            .iter()
+            .fold(input.to_vec(), |values, layer| layer.forward(&values))
+    }
+}
+
+/// Returns the softmax of the `logits` allowed by `mask`, with 0 for the rest
+pub fn masked_softmax(logits: &[f32], mask: &[bool]) -> Vec<f32> {
+    let max = logits
+        .iter()
+        .zip(mask)
+        .filter(|(_, &legal)| legal)
+        .map(|(&logit, _)| logit)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let exps: Vec<f32> = logits
+        .iter()
+        .zip(mask)
+        .map(|(&logit, &legal)| if legal { (logit - max).exp() } else { 0.0 })
+        .collect();
+    let total: f32 = exps.iter().sum();
+    exps.into_iter().map(|exp| exp / total).collect()
+}
+
+/// Plays the moves of a neural network that takes the observations of an `Encoding`
+/// and scores every action index of it. The scores of the legal moves are turned into
+/// chances by a softmax, and the most likely move is made, or one is picked at random
+/// by its chance when sampling.
+/// Positions without a legal move that has an index are played like `Naive`.
+///
+/// Panics on its first move if the network does not fit the encoding of the game.
+pub struct MlpPolicy {
+    net: Arc<Mlp>,
+
+    /// The encoding of the game being played, set up on the first decision
+    encoding: Option<Encoding>,
+
+    /// Whether moves are picked at random by their chance instead of the most likely one
+    sample: bool,
+
+    rng: SmallRng,
+}
+
+impl MlpPolicy {
+    /// # Arguments
+    ///
+    /// * `net` - The network, which may be shared between games
+    /// * `sample` - Picks moves at random by their chance instead of the most likely one
+    /// * `seed` - Seeds the sampling
+    pub fn new(net: Arc<Mlp>, sample: bool, seed: u64) -> Self {
+        Self {
+            net,
+            encoding: None,
+            sample,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for MlpPolicy {
+    fn name(&self) -> &str {
+        "MLP"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let encoding = self.encoding.get_or_insert_with(|| Encoding::of(view));
+        if self.net.inputs() != encoding.observation_size()
+            || self.net.outputs() != encoding.action_count()
+        {
+            panic!(
+                "the network maps {} inputs to {} outputs, but the game encodes {} to {}",
+                self.net.inputs(),
+                self.net.outputs(),
+                encoding.observation_size(),
+                encoding.action_count()
+            );
+        }
+        let mask = encoding.legal_mask(moves);
+        if !mask.contains(&true) {
+            return rollout_move(moves, &mut self.rng);
+        }
+
+        let logits = self.net.forward(&encoding.encode(view));
+        let chances = masked_softmax(&logits, &mask);
+        let best = if self.sample {
+            let mut roll = self.rng.gen::<f32>();
+            let mut picked = None;
+            for (i, &chance) in chances.iter().enumerate() {
+                if mask[i] {
+                    picked = Some(i);
+                    roll -= chance;
+                    if roll < 0.0 {
+                        break;
+                    }
+                }
+            }
+            picked.unwrap()
+        } else {
+            // Ties go to the lowest index
+            (0..chances.len())
+                .filter(|&i| mask[i])
+                .rev()
+                .max_by(|&a, &b| chances[a].total_cmp(&chances[b]))
+                .unwrap()
+        };
+        log::debug!(
+            "seat {} makes {:?} with chance {:.3}",
+            view.seat(),
+            encoding.action(best),
+            chances[best]
+        );
+        encoding.action(best).clone()
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        CardHistogram::new(view.hand())
+            .max()
+            .map(|(color, _)| color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the line and reason `Mlp::parse` rejects `text` with
+    fn parse_error(text: &str) -> (usize, String) {
+        match Mlp::parse(text) {
+            Err(PolicyError::Parse(line, reason)) => (line, reason),
+            Err(err) => panic!("expected a parse error, got {}", err),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn parses_and_runs_a_network() {
+        let net = Mlp::parse(
+            "# mlp v1
+            dense 2 2 relu
+            # comments and blank lines are skipped
+
+            1 -1 0
+            0.5 0.5 -1
+            dense 2 1 linear
+            2 3 0.5",
+        )
+        .unwrap();
+        assert_eq!(net.inputs(), 2);
+        assert_eq!(net.outputs(), 1);
+        // The hidden layer gives relu(3 - 1) = 2 and relu(1.5 + 0.5 - 1) = 1
+        assert_eq!(net.forward(&[3.0, 1.0]), [2.0 * 2.0 + 3.0 * 1.0 + 0.5]);
+    }
+
+    #[test]
+    fn reports_the_line_of_every_error() {
+        assert_eq!(
+            parse_error("dense 1 1 relu\n1 0"),
+            (1, "expected # mlp v1".to_owned())
+        );
+        assert_eq!(parse_error("# mlp v1\n"), (1, "no layers".to_owned()));
+        assert_eq!(
+            parse_error("# mlp v1\n\ndense 1 1 sigmoid\n1 0"),
+            (3, "unknown activation sigmoid".to_owned())
+        );
+        assert_eq!(
+            parse_error("# mlp v1\ndense 0 1 relu\n0"),
+            (2, "bad layer size 0".to_owned())
+        );
+        assert_eq!(
+            parse_error("# mlp v1\ndense 2 1 relu\n1 2"),
+            (3, "expected 2 weights and a bias, got 2".to_owned())
+        );
+        assert_eq!(
+            parse_error("# mlp v1\ndense 1 2 relu\n1 0"),
+            (2, "the layer ends early".to_owned())
+        );
+        let (line, reason) = parse_error("# mlp v1\ndense 1 1 relu\nabc 0");
+        assert_eq!(line, 3);
+        assert!(reason.starts_with("bad weight"), "{}", reason);
+        assert_eq!(
+            parse_error("# mlp v1\ndense 1 2 relu\n1 0\n1 0\ndense 3 1 linear\n1 1 1 0"),
+            (5, "the layer before outputs 2, not 3".to_owned())
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    card::Card,
+    card_counter::{as_dealt, chance_of_any},
+    color::ColorSuite,
+    event::GameEvent,
+    face::Face,
+    reversible_ring::Direction,
+    view::PlayerView,
+};
+
+/// A group of cards the opponent model keeps a belief about
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash)]
+pub enum CardKind {
+    /// Any card of this color, not counting wild cards
+    Color(ColorSuite),
+    /// Any action or wild card with this face
+    Face(Face),
+}
+
+impl CardKind {
+    pub fn matches(&self, card: &Card) -> bool {
+        match self {
+            Self::Color(color) => !card.is_wild() && card.color_suite() == Some(*color),
+            Self::Face(face) => card.face == *face,
+        }
+    }
+}
+
+impl fmt::Display for CardKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Color(color) => write!(f, "{}", color),
+            Self::Face(face) => write!(f, "{}", face),
+        }
+    }
+}
+
+/// What a seat gave away about its hand by drawing instead of playing
+#[derive(Clone, Default)]
+struct Belief {
+    /// The kinds the seat drew instead of playing, with the amount of cards drawn since
+    misses: HashMap<CardKind, usize>,
+
+    /// The amount of cards drawn since the seat drew instead of playing a wild card
+    wild_miss: Option<usize>,
+}
+
+/// Infers what every opponent holds from what they play and what they fail to play.
+/// A player who draws instead of playing on a red card most likely holds
+/// neither a red card nor a wild card, unless they drew on purpose.
+///
+/// The model learns from events alone. Beliefs move along with the hands
+/// when hands are swapped or rotated, since what a player gave away about a hand
+/// stays true of it. Queries take the player's view to weigh those beliefs
+/// against the cards the player has not seen.
+pub struct OpponentModel {
+    /// The chance that a player who could have played draws anyway
+    bluff: f64,
+
+    /// The card currently on top of the discard pile
+    top: Option<Card>,
+
+    /// Whether a plus stack is waiting, so that drawing says nothing about the hand
+    stacked: bool,
+
+    /// What is known about each hand, indexed by the seat holding it
+    beliefs: Vec<Belief>,
+}
+
+impl Default for OpponentModel {
+    fn default() -> Self {
+        Self::new(0.1)
+    }
+}
+
+impl OpponentModel {
+    /// # Arguments
+    ///
+    /// * `bluff` - The chance that a player who could have played draws anyway
+    pub fn new(bluff: f64) -> Self {
+        Self {
+            bluff: bluff.clamp(0.0, 1.0),
+            top: None,
+            stacked: false,
+            beliefs: Vec::new(),
+        }
+    }
+
+    /// Returns every kind of card the game is played with, colors first
+    pub fn kinds(view: &PlayerView) -> Vec<CardKind> {
+        let mut faces: Vec<Face> = view
+            .composition()
+            .iter()
+            .filter(|card| card.is_special())
+            .map(|card| card.face)
+            .collect();
+        faces.sort();
+        faces.dedup();
+        view.colors()
+            .iter()
+            .map(|&color| CardKind::Color(color))
+            .chain(faces.into_iter().map(CardKind::Face))
+            .collect()
+    }
+
+    /// Indicates whether `seat` drew instead of playing a card of `kind`
+    /// and hasn't played one since
+    pub fn lacks(&self, seat: usize, kind: CardKind) -> bool {
+        self.beliefs
+            .get(seat)
+            .is_some_and(|belief| belief.misses.contains_key(&kind))
+    }
+
+    /// Returns the colors out of `colors` that `seat` is believed to lack
+    pub fn lacking(&self, seat: usize, colors: &[ColorSuite]) -> Vec<ColorSuite> {
+        colors
+            .iter()
+            .filter(|&&color| self.lacks(seat, CardKind::Color(color)))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the chance that `seat` holds at least one card of `kind`,
+    /// treating its hand as drawn from the cards `view` has not seen
+    pub fn chance(&self, view: &PlayerView, seat: usize, kind: CardKind) -> f64 {
+        let Some(&size) = view.hand_sizes().get(seat) else {
+            return 0.0;
+        };
+        let unseen = Self::unseen(view);
+        let total = unseen.len();
+        let matching = unseen.iter().filter(|card| kind.matches(card)).count();
+        let wild = matches!(kind, CardKind::Face(face)
+            if view.composition().iter().any(|card| card.is_wild() && card.face == face));
+        let miss = self.beliefs.get(seat).and_then(|belief| {
+            belief
+                .misses
+                .get(&kind)
+                .copied()
+                .or(if wild { belief.wild_miss } else { None })
+        });
+        match miss {
+            None => chance_of_any(total, matching, size),
+            Some(drawn) => {
+                let drawn = drawn.min(size);
+                // How likely the hand held the kind when the seat drew instead of playing it
+                let before = chance_of_any(total, matching, size - drawn);
+                let odds = self.bluff * before + 1.0 - before;
+                let held = if odds > 0.0 {
+                    self.bluff * before / odds
+                } else {
+                    0.0
+                };
+                let since = chance_of_any(total, matching, drawn);
+                1.0 - (1.0 - held) * (1.0 - since)
+            }
+        }
+    }
+
+    /// Returns the chance that `seat` holds each kind of card
+    pub fn beliefs(&self, view: &PlayerView, seat: usize) -> Vec<(CardKind, f64)> {
+        Self::kinds(view)
+            .into_iter()
+            .map(|kind| (kind, self.chance(view, seat, kind)))
+            .collect()
+    }
+
+    /// Returns the cards `view` has not seen, which are either in the deck or in an opponent's hand
+    fn unseen(view: &PlayerView) -> Vec<Card> {
+        let mut unseen: Vec<Card> = view.composition().to_vec();
+        let hand = view.hand().into_iter().copied();
+        for card in view.face_up().into_iter().chain(hand) {
+            if let Some(i) = unseen.iter().position(|other| *other == as_dealt(&card)) {
+                unseen.swap_remove(i);
+            }
+        }
+        unseen
+    }
+
+    /// Returns the belief about `seat`, making room for it if needed
+    fn belief(&mut self, seat: usize) -> &mut Belief {
+        if self.beliefs.len() <= seat {
+            self.beliefs.resize_with(seat + 1, Belief::default);
+        }
+        &mut self.beliefs[seat]
+    }
+
+    pub fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::Dealt { seat, .. } => *self.belief(*seat) = Belief::default(),
+            GameEvent::Flipped { card } => self.top = Some(*card),
+            GameEvent::Played { seat, cards, .. } => {
+                let belief = self.belief(*seat);
+                for card in cards {
+                    // They held the kind after all, so forget they once lacked it
+                    belief.misses.retain(|kind, _| !kind.matches(card));
+                    if card.is_wild() {
+                        belief.wild_miss = None;
+                    }
+                }
+                if let Some(card) = cards.last() {
+                    self.top = Some(*card);
+                    self.stacked = card.face.is_plus();
+                }
+            }
+            GameEvent::ColorChosen { color, .. } => {
+                if let Some(top) = &mut self.top {
+                    if top.color_suite().is_none() {
+                        top.assign_color(*color);
+                    }
+                }
+            }
+            GameEvent::StackResolved { .. } => self.stacked = false,
+            GameEvent::Drew { seat, count } => {
+                let stacked = self.stacked;
+                let top = self.top;
+                let belief = self.belief(*seat);
+                for drawn in belief.misses.values_mut().chain(belief.wild_miss.as_mut()) {
+                    *drawn += count;
+                }
+                if let (false, Some(top)) = (stacked, top) {
+                    // Drawing instead of playing gives away everything that could go on top
+                    if let Some(color) = top.color_suite() {
+                        belief.misses.insert(CardKind::Color(color), *count);
+                    }
+                    if top.is_special() {
+                        belief.misses.insert(CardKind::Face(top.face), *count);
+                    }
+                    belief.wild_miss = Some(*count);
+                }
+            }
+            GameEvent::HandsSwapped { seat, other } => {
+                self.belief((*seat).max(*other));
+                self.beliefs.swap(*seat, *other);
+            }
+            // Hands move the same way as in `GameState::hands`
+            GameEvent::HandsRotated { direction } => match direction {
+                Direction::Clockwise => self.beliefs.rotate_right(1),
+                Direction::CounterClockwise => self.beliefs.rotate_left(1),
+            },
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dealt(model: &mut OpponentModel, seats: usize) {
+        for seat in 0..seats {
+            model.on_event(&GameEvent::Dealt { seat, count: 7 });
+        }
+    }
+
+    #[test]
+    fn drawing_on_a_color_marks_it_lacking_until_it_is_played() {
+        let mut model = OpponentModel::default();
+        dealt(&mut model, 3);
+        model.on_event(&GameEvent::Flipped {
+            card: Card::new_colored(Face::Skip, ColorSuite::Red),
+        });
+        model.on_event(&GameEvent::Drew { seat: 1, count: 1 });
+        assert!(model.lacks(1, CardKind::Color(ColorSuite::Red)));
+        assert!(model.lacks(1, CardKind::Face(Face::Skip)));
+        assert!(!model.lacks(2, CardKind::Color(ColorSuite::Red)));
+
+        model.on_event(&GameEvent::Played {
+            seat: 1,
+            target: 0,
+            cards: vec![Card::new_colored(Face::Number(3), ColorSuite::Red)],
+        });
+        assert!(!model.lacks(1, CardKind::Color(ColorSuite::Red)));
+        assert!(model.lacks(1, CardKind::Face(Face::Skip)));
+    }
+
+    #[test]
+    fn drawing_on_a_plus_stack_says_nothing() {
+        let mut model = OpponentModel::default();
+        dealt(&mut model, 2);
+        model.on_event(&GameEvent::Played {
+            seat: 0,
+            target: 0,
+            cards: vec![Card::new_colored(Face::PlusTwo, ColorSuite::Blue)],
+        });
+        model.on_event(&GameEvent::Drew { seat: 1, count: 2 });
+        assert!(model.lacking(1, &[ColorSuite::Blue]).is_empty());
+    }
+
+    #[test]
+    fn beliefs_follow_the_hands() {
+        let mut model = OpponentModel::default();
+        dealt(&mut model, 3);
+        model.on_event(&GameEvent::Flipped {
+            card: Card::new_colored(Face::Number(5), ColorSuite::Green),
+        });
+        model.on_event(&GameEvent::Drew { seat: 0, count: 1 });
+
+        model.on_event(&GameEvent::HandsSwapped { seat: 0, other: 2 });
+        assert_eq!(model.lacking(2, &[ColorSuite::Green]), [ColorSuite::Green]);
+        assert!(model.lacking(0, &[ColorSuite::Green]).is_empty());
+
+        model.on_event(&GameEvent::HandsRotated {
+            direction: Direction::Clockwise,
+        });
+        assert_eq!(model.lacking(0, &[ColorSuite::Green]), [ColorSuite::Green]);
+    }
+}
@@ -1,27 +1,100 @@
 use std::time::Duration;
 
+use rand::{thread_rng, Rng};
+
 #[derive(Copy, Clone)]
 pub struct Options {
     pub num_threads: usize,
-    pub slow: bool,
+    pub turn_pause: Duration,
     pub verbose: bool,
+
+    /// Makes every game deal the same way on every run.
+    /// Game `i` of a run is seeded with `seed + i`.
+    pub seed: Option<u64>,
+
+    /// Prints nothing at all, not even the winner of every game
+    pub quiet: bool,
+
+    /// Lets strategies that support it, like the endgame search, see every hidden card.
+    /// This is cheating and only meant for analysis.
+    pub perfect_information: bool,
 }
 
 impl Options {
-    pub fn new(num_threads: usize, slow: bool, verbose: bool) -> Self {
+    pub fn new(num_threads: usize, turn_pause: Duration, verbose: bool) -> Self {
         Self {
             num_threads,
-            slow,
+            turn_pause,
             verbose,
+            seed: None,
+            quiet: false,
+            perfect_information: false,
+        }
+    }
+
+    /// Seeds every game of a run, see `Options::seed`
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Silences every game of a run, see `Options::quiet`
+    pub fn quiet(mut self) -> Self {
+        self.quiet = true;
+        self
+    }
+
+    /// Shows every hidden card to the strategies that ask, see `Options::perfect_information`
+    pub fn perfect_information(mut self) -> Self {
+        self.perfect_information = true;
+        self
+    }
+
+    /// Returns the options that apply to game `game` of a run
+    pub fn game_options(&self, game: usize) -> GameOptions {
+        GameOptions {
+            verbose: self.verbose,
+            turn_pause: self.turn_pause,
+            seed: self.seed.map(|seed| seed.wrapping_add(game as u64)),
+            quiet: self.quiet,
+            perfect_information: self.perfect_information,
         }
     }
+}
 
-    pub fn default() -> Self {
-        Self::new(4, false, false)
+impl Default for Options {
+    fn default() -> Self {
+        Self::new(4, Duration::ZERO, false)
     }
 }
 
+#[derive(Copy, Clone)]
 pub struct GameOptions {
     pub verbose: bool,
     pub turn_pause: Duration,
+    pub seed: Option<u64>,
+    pub quiet: bool,
+    pub perfect_information: bool,
+}
+
+impl GameOptions {
+    /// Returns the seed of the strategy playing in `seat`.
+    /// It only depends on the seed of the game and the seat,
+    /// so a seeded game is played the same way whichever thread runs it.
+    /// Strategies of an unseeded game are seeded at random.
+    pub fn strategy_seed(&self, seat: usize) -> u64 {
+        match self.seed {
+            Some(seed) => seat_seed(seed, seat),
+            None => thread_rng().gen(),
+        }
+    }
+}
+
+/// Mixes the seed of a game with `seat` into the seed of that seat's strategy,
+/// by a round of SplitMix64, so that neighboring games and seats get unrelated seeds
+pub(crate) fn seat_seed(game_seed: u64, seat: usize) -> u64 {
+    let mut z = game_seed.wrapping_add((seat as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
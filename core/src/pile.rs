@@ -1,5 +1,6 @@
 use crate::card::Card;
 
+#[derive(Clone)]
 pub struct Pile {
     stack: Vec<Card>,
 }
@@ -9,25 +10,31 @@ impl Pile {
         Self { stack: Vec::new() }
     }
 
-    /// Returns the head and tail of the pile.
-    /// This does not mutate the pile.
-    fn head_tail(&self) -> (Card, Vec<Card>) {
-        let head = self.stack[0];
-        let tail = &self.stack[1..];
-        return (head, tail.to_vec());
-    }
-
+    /// Removes every card except the top card and returns them.
     pub fn reduce_to_top(&mut self) -> Vec<Card> {
-        let (head, tail) = self.head_tail();
-        self.stack = vec![head];
-        return tail;
+        let top = self.stack.split_off(self.stack.len() - 1);
+        std::mem::replace(&mut self.stack, top)
     }
 
     pub fn add(&mut self, card: Card) {
         self.stack.push(card)
     }
 
+    /// Returns the cards of the pile from bottom to top
+    pub fn cards(&self) -> &[Card] {
+        &self.stack
+    }
+
+    /// Consumes the pile and returns its cards from bottom to top
+    pub fn into_cards(self) -> Vec<Card> {
+        self.stack
+    }
+
     pub fn top(&self) -> &Card {
-        return self.stack.last().unwrap();
+        self.stack.last().unwrap()
+    }
+
+    pub fn top_mut(&mut self) -> &mut Card {
+        self.stack.last_mut().unwrap()
     }
 }
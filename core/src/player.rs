@@ -1,49 +1,27 @@
 use std::fmt;
 
-use crate::{card::Card, hand::Hand, rules::Rules, strategy::Strategy};
+use crate::strategy::{Strategy, StrategyFactory};
 
-#[derive(Clone, Eq, PartialEq, Hash)]
+#[derive(Clone)]
 pub struct Player {
     pub id: u32,
     pub name: String,
-    pub hand: Hand,
-    strategy: Strategy,
-}
-
-pub struct PlayResult {
-    pub card: Option<Card>,
-    pub was_last_card: bool,
-}
-
-impl PlayResult {
-    pub fn new(card: Option<Card>, was_last_card: bool) -> Self {
-        Self {
-            card,
-            was_last_card,
-        }
-    }
+    strategy: StrategyFactory,
 }
 
 impl Player {
-    pub fn new(id: u32, name: String, strategy: Strategy) -> Self {
-        Self {
-            id,
-            hand: Hand::new(),
-            strategy,
-            name,
-        }
+    pub fn new(id: u32, name: String, strategy: StrategyFactory) -> Self {
+        Self { id, name, strategy }
     }
 
-    pub fn play(&mut self, card: &Card, rules: &Rules, plus_stacking: bool) -> PlayResult {
-        self.strategy
-            .run(card, &mut self.hand, rules, plus_stacking)
+    /// Builds a fresh instance of this player's strategy for a new game,
+    /// making its random decisions from `seed`
+    pub fn new_strategy(&self, seed: u64) -> Box<dyn Strategy> {
+        self.strategy.build(seed)
     }
 
-    pub fn proclaim_victory(&self) {
-        println!(
-            "{} has won using the {} strategy",
-            self.name, self.strategy.name
-        )
+    pub fn strategy_name(&self) -> &str {
+        &self.strategy.name
     }
 }
 
@@ -0,0 +1,8 @@
+//! The types needed to write a strategy and run games with it.
+//! Glob import it with `use core::prelude::*;`.
+
+pub use crate::{
+    Action, Card, Color, ColorChooser, ColorSuite, DeckSpec, Direction, Face, GameEvent,
+    GameMasterBuilder, GameSetup, Hand, Observer, Options, Params, PlayerView, PlusStacking, Rules,
+    RulesBuilder, Simulation, Strategy, StrategyEntry, StrategyFactory, StrategyRegistry,
+};
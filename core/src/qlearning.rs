@@ -0,0 +1,664 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    action::Action, card::Card, color::ColorSuite, face::Face, hand_analysis::CardHistogram,
+    search::rollout_move, strategy::Strategy, strategy::StrategyFactory, view::PlayerView,
+};
+
+/// The first line of every policy file
+const HEADER: &str = "# q-table v1";
+
+/// Caps `n` at `max`, so that large counts share a bucket
+fn bucket(n: usize, max: u8) -> u8 {
+    n.min(max as usize) as u8
+}
+
+/// Buckets a hand size, keeping the sizes close to going out apart
+fn size_bucket(size: usize) -> u8 {
+    match size {
+        0..=3 => size as u8,
+        4..=6 => 4,
+        _ => 5,
+    }
+}
+
+/// The kind of card on top of the discard pile
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+enum TopKind {
+    Number,
+    Skip,
+    Reverse,
+    PlusTwo,
+    Wild,
+    PlusFour,
+}
+
+impl TopKind {
+    const ALL: [Self; 6] = [
+        Self::Number,
+        Self::Skip,
+        Self::Reverse,
+        Self::PlusTwo,
+        Self::Wild,
+        Self::PlusFour,
+    ];
+
+    fn of(card: &Card) -> Self {
+        match card.face {
+            Face::Number(_) | Face::Rank(_) => Self::Number,
+            Face::Skip => Self::Skip,
+            Face::Reverse => Self::Reverse,
+            Face::PlusTwo => Self::PlusTwo,
+            Face::Wild => Self::Wild,
+            Face::PlusFour => Self::PlusFour,
+        }
+    }
+}
+
+/// What a Q-table knows of a position.
+/// The hand is counted relative to the top card, so that the colors themselves don't matter.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
+pub struct QState {
+    /// Cards in the hand of the top card's color, up to 3
+    same_color: u8,
+
+    /// Cards in the hand of another color showing the top card's face, up to 2
+    same_face: u8,
+
+    /// Every other colored card in the hand, up to 5
+    other: u8,
+
+    /// Colored skips, reverses and plus twos in the hand, up to 2
+    actions: u8,
+
+    /// Wild cards in the hand, up to 2
+    wilds: u8,
+
+    top: TopKind,
+
+    /// The hand size of the next seat, bucketed
+    next: u8,
+
+    /// The smallest hand of any opponent, bucketed
+    fewest: u8,
+
+    /// Whether a plus stack is waiting to be taken
+    stacked: bool,
+}
+
+impl QState {
+    /// Abstracts what the player of `view` can see
+    pub fn new(view: &PlayerView) -> Self {
+        let top = view.top();
+        let top_color = top.color_suite();
+        let (mut same_color, mut same_face, mut other, mut actions, mut wilds) = (0, 0, 0, 0, 0);
+        for card in view.hand() {
+            if card.is_wild() {
+                wilds += 1;
+                continue;
+            }
+            if card.is_special() {
+                actions += 1;
+            }
+            if card.color_suite() == top_color {
+                same_color += 1;
+            } else if card.face == top.face {
+                same_face += 1;
+            } else {
+                other += 1;
+            }
+        }
+        let sizes = view.hand_sizes();
+        let fewest = (0..view.players())
+            .filter(|&seat| seat != view.seat())
+            .map(|seat| sizes[seat])
+            .min()
+            .unwrap_or(0);
+        Self {
+            same_color: bucket(same_color, 3),
+            same_face: bucket(same_face, 2),
+            other: bucket(other, 5),
+            actions: bucket(actions, 2),
+            wilds: bucket(wilds, 2),
+            top: TopKind::of(top),
+            next: size_bucket(sizes[view.next_seat()]),
+            fewest: size_bucket(fewest),
+            stacked: view.stack_count() > 0,
+        }
+    }
+}
+
+impl Display for QState {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {} {}",
+            self.same_color,
+            self.same_face,
+            self.other,
+            self.actions,
+            self.wilds,
+            self.top as u8,
+            self.next,
+            self.fewest,
+            u8::from(self.stacked)
+        )
+    }
+}
+
+impl FromStr for QState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s
+            .split_whitespace()
+            .map(|field| field.parse::<u8>())
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|err| format!("bad state {}: {}", s, err))?;
+        let [same_color, same_face, other, actions, wilds, top, next, fewest, stacked] = fields[..]
+        else {
+            return Err(format!("expected 9 state fields, got {}", fields.len()));
+        };
+        let top = *TopKind::ALL
+            .get(top as usize)
+            .ok_or_else(|| format!("unknown top card kind {}", top))?;
+        Ok(Self {
+            same_color,
+            same_face,
+            other,
+            actions,
+            wilds,
+            top,
+            next,
+            fewest,
+            stacked: stacked != 0,
+        })
+    }
+}
+
+/// What a Q-table knows of a move.
+/// Several legal moves can share a kind, in which case the one shedding the most points is made.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+pub enum MoveKind {
+    /// Plays a number card of the top card's color
+    NumberOnColor,
+
+    /// Plays a number card of another color
+    NumberOnFace,
+    Skip,
+    Reverse,
+    PlusTwo,
+    Wild,
+    PlusFour,
+
+    /// Lays a card down as a new target
+    Place,
+    Swap,
+    Draw,
+    Pass,
+}
+
+impl MoveKind {
+    pub const ALL: [Self; 11] = [
+        Self::NumberOnColor,
+        Self::NumberOnFace,
+        Self::Skip,
+        Self::Reverse,
+        Self::PlusTwo,
+        Self::Wild,
+        Self::PlusFour,
+        Self::Place,
+        Self::Swap,
+        Self::Draw,
+        Self::Pass,
+    ];
+
+    /// Returns the kind of `action`, made while `top` is on the discard pile
+    pub fn of(action: &Action, top: &Card) -> Self {
+        match action {
+            Action::Play { cards, .. } => match cards[0].face {
+                Face::Skip => Self::Skip,
+                Face::Reverse => Self::Reverse,
+                Face::PlusTwo => Self::PlusTwo,
+                Face::Wild => Self::Wild,
+                Face::PlusFour => Self::PlusFour,
+                _ if cards[0].color_suite() == top.color_suite() => Self::NumberOnColor,
+                _ => Self::NumberOnFace,
+            },
+            Action::Place { .. } => Self::Place,
+            Action::Swap { .. } => Self::Swap,
+            Action::Pass => Self::Pass,
+            _ => Self::Draw,
+        }
+    }
+
+    /// Returns the distinct kinds of `moves`, in the order they first appear
+    fn of_moves(moves: &[Action], top: &Card) -> Vec<Self> {
+        let mut kinds = Vec::new();
+        for action in moves {
+            let kind = Self::of(action, top);
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+
+    /// Picks the move of this kind out of `moves` that sheds the most points,
+    /// declaring the color the hand holds most for wild cards
+    fn pick(self, moves: &[Action], view: &PlayerView) -> Action {
+        let top = view.top();
+        let mut action = moves
+            .iter()
+            .filter(|action| Self::of(action, top) == self)
+            .max_by_key(|action| action.cards().iter().map(Card::points).sum::<usize>())
+            .unwrap_or(&moves[0])
+            .clone();
+        let wild = action.cards().iter().any(Card::is_wild);
+        if let Action::Play { color, .. } | Action::Place { color, .. } = &mut action {
+            if wild {
+                *color = CardHistogram::new(view.hand())
+                    .max()
+                    .map(|(most, _)| most)
+                    .or_else(|| view.colors().first().copied());
+            }
+        }
+        action
+    }
+}
+
+impl Display for MoveKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Self::NumberOnColor => "number-on-color",
+            Self::NumberOnFace => "number-on-face",
+            Self::Skip => "skip",
+            Self::Reverse => "reverse",
+            Self::PlusTwo => "plus-two",
+            Self::Wild => "wild",
+            Self::PlusFour => "plus-four",
+            Self::Place => "place",
+            Self::Swap => "swap",
+            Self::Draw => "draw",
+            Self::Pass => "pass",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for MoveKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| format!("unknown move kind {}", s))
+    }
+}
+
+/// Why a policy or weights file could not be read
+#[derive(Debug)]
+pub enum PolicyError {
+    Io(io::Error),
+
+    /// The line number and what is wrong with it
+    Parse(usize, String),
+}
+
+impl Display for PolicyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Parse(line, reason) => write!(f, "line {}: {}", line, reason),
+        }
+    }
+}
+
+impl Error for PolicyError {}
+
+impl From<io::Error> for PolicyError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The learned value of every move kind in every abstract state.
+/// Values estimate the chance of winning after the move, so unseen entries count as 0.
+#[derive(Clone, Debug, Default)]
+pub struct QTable {
+    values: HashMap<(QState, MoveKind), f64>,
+}
+
+impl QTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The amount of entries learned so far
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the learned value of `kind` in `state`, if there is one
+    pub fn get(&self, state: &QState, kind: MoveKind) -> Option<f64> {
+        self.values.get(&(state.clone(), kind)).copied()
+    }
+
+    /// Returns the best of `kinds` in `state` along with its value.
+    /// Ties go to the kind listed first, and kinds never learned count as 0.
+    pub fn best(&self, state: &QState, kinds: &[MoveKind]) -> Option<(MoveKind, f64)> {
+        kinds
+            .iter()
+            .rev()
+            .map(|&kind| (kind, self.get(state, kind).unwrap_or(0.0)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    /// Moves the value of `kind` in `state` a step of `alpha` towards `target`
+    pub fn update(&mut self, state: QState, kind: MoveKind, target: f64, alpha: f64) {
+        let value = self.values.entry((state, kind)).or_insert(0.0);
+        *value += alpha * (target - *value);
+    }
+
+    /// Writes the table to `path`, one tab separated entry per line, sorted so that
+    /// the same table always gives the same file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut entries: Vec<_> = self.values.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+        let mut lines = vec![HEADER.to_owned()];
+        for ((state, kind), value) in entries {
+            lines.push(format!("{}\t{}\t{}", state, kind, value));
+        }
+        lines.push(String::new());
+        fs::write(path, lines.join("\n"))
+    }
+
+    /// Reads a table written by `save`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PolicyError> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(PolicyError::Parse(1, format!("expected {}", HEADER)));
+        }
+        let mut table = Self::new();
+        for (i, line) in lines {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parse = || -> Result<(QState, MoveKind, f64), String> {
+                let mut fields = line.split('\t');
+                let mut next = || fields.next().ok_or("expected state, move and value");
+                let state = next()?.parse()?;
+                let kind = next()?.parse()?;
+                let value = next()?
+                    .parse()
+                    .map_err(|err| format!("bad value: {}", err))?;
+                Ok((state, kind, value))
+            };
+            let (state, kind, value) =
+                parse().map_err(|reason| PolicyError::Parse(i + 1, reason))?;
+            table.values.insert((state, kind), value);
+        }
+        Ok(table)
+    }
+}
+
+/// How fast and how greedily a `QLearner` learns
+#[derive(Copy, Clone, Debug)]
+pub struct LearningRates {
+    /// How far every update moves a value towards its target
+    pub alpha: f64,
+
+    /// How much the value of the next decision counts towards the one before it
+    pub gamma: f64,
+
+    /// The chance of making a random move instead of the best one
+    pub epsilon: f64,
+}
+
+impl Default for LearningRates {
+    fn default() -> Self {
+        Self {
+            alpha: 0.1,
+            gamma: 0.98,
+            epsilon: 0.1,
+        }
+    }
+}
+
+/// Learns a shared `QTable` while it plays, exploring with a random move
+/// `epsilon` of the time. Every decision updates the one before it,
+/// and the last decision of a game is rewarded with 1 for a win and 0 otherwise.
+pub struct QLearner {
+    table: Arc<Mutex<QTable>>,
+    rates: LearningRates,
+    rng: SmallRng,
+
+    /// The last decision made, waiting for the value of the next one
+    last: Option<(QState, MoveKind)>,
+}
+
+impl QLearner {
+    /// # Arguments
+    ///
+    /// * `table` - The table to learn, which may be shared with other learners
+    /// * `rates` - How fast and how greedily to learn
+    /// * `seed` - Seeds the exploration
+    pub fn new(table: Arc<Mutex<QTable>>, rates: LearningRates, seed: u64) -> Self {
+        Self {
+            table,
+            rates,
+            rng: SmallRng::seed_from_u64(seed),
+            last: None,
+        }
+    }
+}
+
+impl Strategy for QLearner {
+    fn name(&self) -> &str {
+        "QLearner"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let state = QState::new(view);
+        let kinds = MoveKind::of_moves(moves, view.top());
+        let mut table = self.table.lock().unwrap();
+        let (best, value) = table.best(&state, &kinds).unwrap();
+        if let Some((last, kind)) = self.last.take() {
+            table.update(last, kind, self.rates.gamma * value, self.rates.alpha);
+        }
+        drop(table);
+
+        let kind = if self.rng.gen_bool(self.rates.epsilon.clamp(0.0, 1.0)) {
+            *kinds.choose(&mut self.rng).unwrap()
+        } else {
+            best
+        };
+        self.last = Some((state, kind));
+        kind.pick(moves, view)
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        CardHistogram::new(view.hand())
+            .max()
+            .map(|(color, _)| color)
+    }
+
+    fn on_game_end(&mut self, won: bool) {
+        if let Some((last, kind)) = self.last.take() {
+            let reward = if won { 1.0 } else { 0.0 };
+            self.table
+                .lock()
+                .unwrap()
+                .update(last, kind, reward, self.rates.alpha);
+        }
+    }
+}
+
+/// Plays the best move kind of a learned `QTable`.
+/// Positions the table never learned are played like `Naive`.
+pub struct QPolicy {
+    table: Arc<QTable>,
+    rng: SmallRng,
+}
+
+impl QPolicy {
+    /// # Arguments
+    ///
+    /// * `table` - The learned table
+    /// * `seed` - Seeds the moves made where the table knows nothing
+    pub fn new(table: Arc<QTable>, seed: u64) -> Self {
+        Self {
+            table,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for QPolicy {
+    fn name(&self) -> &str {
+        "QPolicy"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let state = QState::new(view);
+        let kinds = MoveKind::of_moves(moves, view.top());
+        if kinds
+            .iter()
+            .all(|&kind| self.table.get(&state, kind).is_none())
+        {
+            return rollout_move(moves, &mut self.rng);
+        }
+        let (kind, value) = self.table.best(&state, &kinds).unwrap();
+        log::debug!(
+            "seat {} values {} at {:.3} in state {}",
+            view.seat(),
+            kind,
+            value,
+            state
+        );
+        kind.pick(moves, view)
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        CardHistogram::new(view.hand())
+            .max()
+            .map(|(color, _)| color)
+    }
+}
+
+/// Trains a `QTable` by self-play. Every seat seated with `learner`
+/// learns the same table, so games can be run through a `GameMaster` on any amount of threads.
+pub struct QTrainer {
+    table: Arc<Mutex<QTable>>,
+    rates: LearningRates,
+
+    /// Mixed with the seed of every game, so that every game explores differently
+    seed: u64,
+}
+
+impl QTrainer {
+    /// # Arguments
+    ///
+    /// * `table` - The table to go on learning, which is empty to start from scratch
+    /// * `rates` - How fast and how greedily to learn
+    /// * `seed` - Seeds the exploration
+    pub fn new(table: QTable, rates: LearningRates, seed: u64) -> Self {
+        Self {
+            table: Arc::new(Mutex::new(table)),
+            rates,
+            seed,
+        }
+    }
+
+    /// Returns a strategy that learns the table while it plays
+    pub fn learner(&self) -> StrategyFactory {
+        let (table, rates, base) = (self.table.clone(), self.rates, self.seed);
+        StrategyFactory::seeded("QLearner", move |seed| {
+            Box::new(QLearner::new(table.clone(), rates, base.wrapping_add(seed)))
+        })
+    }
+
+    /// Returns a strategy that plays what was learned so far, without learning any further
+    pub fn policy(&self) -> StrategyFactory {
+        policy_factory(self.table(), 0)
+    }
+
+    /// Returns a copy of the table learned so far
+    pub fn table(&self) -> QTable {
+        self.table.lock().unwrap().clone()
+    }
+}
+
+/// Returns a strategy that plays `table`, mixing `seed` with the seed of every game
+pub fn policy_factory(table: QTable, seed: u64) -> StrategyFactory {
+    let table = Arc::new(table);
+    StrategyFactory::seeded("QPolicy", move |game_seed| {
+        Box::new(QPolicy::new(table.clone(), seed.wrapping_add(game_seed)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a file path in the temporary directory that no other test uses
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("uno-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn a_saved_table_loads_back_the_same() {
+        let mut table = QTable::new();
+        let state: QState = "1 0 2 0 1 3 2 1 0".parse().unwrap();
+        let stacked: QState = "0 0 0 0 0 4 1 1 1".parse().unwrap();
+        table.update(state.clone(), MoveKind::NumberOnColor, 0.75, 1.0);
+        table.update(state.clone(), MoveKind::Draw, 0.125, 1.0);
+        table.update(stacked.clone(), MoveKind::PlusFour, 0.5, 0.5);
+
+        let path = temp_file("round-trip.txt");
+        table.save(&path).unwrap();
+        let loaded = QTable::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.get(&state, MoveKind::NumberOnColor), Some(0.75));
+        assert_eq!(loaded.get(&state, MoveKind::Draw), Some(0.125));
+        assert_eq!(loaded.get(&stacked, MoveKind::PlusFour), Some(0.25));
+        assert_eq!(loaded.get(&stacked, MoveKind::Wild), None);
+    }
+
+    #[test]
+    fn loading_reports_the_line_of_a_bad_entry() {
+        let path = temp_file("bad-entry.txt");
+        let text = format!(
+            "{}\n1 0 2 0 1 3 2 1 0\tdraw\t0.5\n1 0 2 0 1 3 2 1 0\tdance\t0.5\n",
+            HEADER
+        );
+        fs::write(&path, text).unwrap();
+        let err = QTable::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(err.to_string(), "line 3: unknown move kind dance");
+
+        let path = temp_file("no-header.txt");
+        fs::write(&path, "1 0 2 0 1 3 2 1 0\tdraw\t0.5\n").unwrap();
+        let err = QTable::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(err, PolicyError::Parse(1, _)));
+    }
+}
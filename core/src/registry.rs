@@ -0,0 +1,923 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    algorithms::{Aggressive, Counting, Defensive, Greedy},
+    color_chooser::{self, ColorChooserFactory, COLOR_CHOOSERS},
+    decision_tree::DecisionTree,
+    endgame::{Endgame, EndgameSolver},
+    heuristic::{Heuristic, FEATURES},
+    mlp::{Mlp, MlpPolicy},
+    qlearning::{policy_factory, QTable},
+    script::Script,
+    search::{Ismcts, MonteCarlo},
+    strategy::StrategyFactory,
+};
+
+/// The type of a strategy parameter
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ParamKind {
+    Int,
+    Float,
+    Bool,
+    Text,
+}
+
+impl Display for ParamKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Int => write!(f, "integer"),
+            Self::Float => write!(f, "number"),
+            Self::Bool => write!(f, "true or false"),
+            Self::Text => write!(f, "text"),
+        }
+    }
+}
+
+/// A parsed strategy parameter
+#[derive(PartialEq, Clone, Debug)]
+pub enum ParamValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl ParamValue {
+    /// Parses `value` as a parameter of type `kind`
+    fn parse(kind: ParamKind, value: &str) -> Option<Self> {
+        match kind {
+            ParamKind::Int => value.parse().ok().map(Self::Int),
+            ParamKind::Float => value.parse().ok().map(Self::Float),
+            ParamKind::Bool => value.parse().ok().map(Self::Bool),
+            ParamKind::Text => Some(Self::Text(value.to_owned())),
+        }
+    }
+}
+
+/// Describes a parameter a strategy accepts
+#[derive(Clone, Debug)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub kind: ParamKind,
+
+    /// The value used when the config leaves the parameter out
+    pub default: &'static str,
+    pub doc: &'static str,
+}
+
+/// The validated parameters a strategy is built with.
+/// Every declared parameter is present, falling back to its default.
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    values: HashMap<&'static str, ParamValue>,
+}
+
+impl Params {
+    /// Returns an integer parameter
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strategy did not declare `name` as an integer
+    pub fn int(&self, name: &str) -> i64 {
+        match self.values.get(name) {
+            Some(ParamValue::Int(n)) => *n,
+            _ => panic!("{} is not an integer parameter", name),
+        }
+    }
+
+    /// Returns a number parameter
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strategy did not declare `name` as a number
+    pub fn float(&self, name: &str) -> f64 {
+        match self.values.get(name) {
+            Some(ParamValue::Float(n)) => *n,
+            _ => panic!("{} is not a number parameter", name),
+        }
+    }
+
+    /// Returns a boolean parameter
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strategy did not declare `name` as a boolean
+    pub fn bool(&self, name: &str) -> bool {
+        match self.values.get(name) {
+            Some(ParamValue::Bool(b)) => *b,
+            _ => panic!("{} is not a boolean parameter", name),
+        }
+    }
+
+    /// Returns a text parameter
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strategy did not declare `name` as text
+    pub fn text(&self, name: &str) -> &str {
+        match self.values.get(name) {
+            Some(ParamValue::Text(s)) => s,
+            _ => panic!("{} is not a text parameter", name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StrategyError {
+    UnknownStrategy(String),
+    UnknownColorChooser(String),
+
+    /// The strategy and the parameter it does not take
+    UnknownParam(String, String),
+
+    /// The parameter, the value it was given and the type it expects
+    InvalidParam(String, String, ParamKind),
+
+    /// The strategy and why it could not be built from valid parameters,
+    /// such as a policy file that failed to load
+    Build(String, String),
+}
+
+impl Display for StrategyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownStrategy(name) => write!(f, "unknown strategy {}", name),
+            Self::UnknownColorChooser(name) => write!(
+                f,
+                "unknown color chooser {}, expected one of {}",
+                name,
+                COLOR_CHOOSERS.map(|(name, _, _)| name).join(", ")
+            ),
+            Self::UnknownParam(strategy, param) => {
+                write!(f, "the {} strategy has no parameter {}", strategy, param)
+            }
+            Self::InvalidParam(param, value, kind) => {
+                write!(
+                    f,
+                    "{} is not a valid value for {}, expected {}",
+                    value, param, kind
+                )
+            }
+            Self::Build(strategy, reason) => {
+                write!(f, "could not build the {} strategy: {}", strategy, reason)
+            }
+        }
+    }
+}
+
+impl Error for StrategyError {}
+
+/// How a `StrategyEntry` builds its strategy
+#[derive(Clone, Copy)]
+enum Build {
+    Infallible(fn(&Params) -> StrategyFactory),
+    Fallible(fn(&Params) -> Result<StrategyFactory, String>),
+    Wrapping(fn(&Params, StrategyFactory) -> StrategyFactory),
+}
+
+/// The parameter naming the strategy a wrapping strategy plays around,
+/// see `StrategyEntry::wrapping`
+const INNER: &str = "inner";
+
+/// A strategy that can be built by name
+pub struct StrategyEntry {
+    pub name: &'static str,
+    pub doc: &'static str,
+    pub params: Vec<ParamSpec>,
+    build: Build,
+}
+
+impl StrategyEntry {
+    /// # Arguments
+    ///
+    /// * `name` - The name configs refer to the strategy by
+    /// * `doc` - A one line description of how the strategy plays
+    /// * `params` - The parameters the strategy accepts
+    /// * `build` - Builds the strategy from validated parameters
+    pub fn new(
+        name: &'static str,
+        doc: &'static str,
+        params: Vec<ParamSpec>,
+        build: fn(&Params) -> StrategyFactory,
+    ) -> Self {
+        Self {
+            name,
+            doc,
+            params,
+            build: Build::Infallible(build),
+        }
+    }
+
+    /// Describes a strategy whose construction can fail even with valid parameters,
+    /// such as one that loads a file
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the strategy is built by
+    /// * `doc` - A single line describing the strategy
+    /// * `params` - The parameters the strategy accepts
+    /// * `build` - Builds the strategy from validated parameters, or explains why it can't
+    pub fn fallible(
+        name: &'static str,
+        doc: &'static str,
+        params: Vec<ParamSpec>,
+        build: fn(&Params) -> Result<StrategyFactory, String>,
+    ) -> Self {
+        Self {
+            name,
+            doc,
+            params,
+            build: Build::Fallible(build),
+        }
+    }
+
+    /// Describes a strategy that plays around another strategy,
+    /// which the registry builds from the text parameter `inner`.
+    /// Parameters of the inner strategy are given with an `inner.` prefix,
+    /// such as `inner.threat`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the strategy is built by
+    /// * `doc` - A single line describing the strategy
+    /// * `params` - The parameters the strategy accepts, including `inner`
+    /// * `build` - Builds the strategy from validated parameters and the inner strategy
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `params` declares `inner` as text
+    pub fn wrapping(
+        name: &'static str,
+        doc: &'static str,
+        params: Vec<ParamSpec>,
+        build: fn(&Params, StrategyFactory) -> StrategyFactory,
+    ) -> Self {
+        if !params
+            .iter()
+            .any(|spec| spec.name == INNER && spec.kind == ParamKind::Text)
+        {
+            panic!(
+                "{} wraps another strategy but has no text parameter {}",
+                name, INNER
+            );
+        }
+        Self {
+            name,
+            doc,
+            params,
+            build: Build::Wrapping(build),
+        }
+    }
+
+    /// Validates `params` against the declared parameters, filling in defaults
+    fn parse_params(&self, params: &[(String, String)]) -> Result<Params, StrategyError> {
+        let mut parsed = Params::default();
+        for spec in self.params.iter() {
+            let value = ParamValue::parse(spec.kind, spec.default)
+                .unwrap_or_else(|| panic!("bad default for {}.{}", self.name, spec.name));
+            parsed.values.insert(spec.name, value);
+        }
+        for (name, value) in params {
+            let spec = self
+                .params
+                .iter()
+                .find(|spec| spec.name == name)
+                .ok_or_else(|| StrategyError::UnknownParam(self.name.to_owned(), name.clone()))?;
+            let value = ParamValue::parse(spec.kind, value).ok_or_else(|| {
+                StrategyError::InvalidParam(name.clone(), value.clone(), spec.kind)
+            })?;
+            parsed.values.insert(spec.name, value);
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the name and description of the strategy followed by a line for every parameter
+    pub fn describe(&self) -> String {
+        let mut lines = vec![format!("{:<16}{}", self.name, self.doc)];
+        for spec in self.params.iter() {
+            lines.push(format!(
+                "    {} ({}, default {}): {}",
+                spec.name, spec.kind, spec.default, spec.doc
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Every strategy that can be built by name
+pub struct StrategyRegistry {
+    entries: Vec<StrategyEntry>,
+}
+
+impl StrategyRegistry {
+    /// Constructs a registry holding only the strategies built into this crate
+    pub fn new() -> Self {
+        let mut registry = Self {
+            entries: Vec::new(),
+        };
+        registry.register(StrategyEntry::new(
+            "naive",
+            "Plays a random card whenever it can",
+            Vec::new(),
+            |_| StrategyFactory::naive(),
+        ));
+        registry.register(StrategyEntry::new(
+            "greedy",
+            "Plays the card that keeps the most of its hand playable",
+            vec![ParamSpec {
+                name: "hold_wilds_until",
+                kind: ParamKind::Int,
+                default: "2",
+                doc: "Hand size at or below which wild cards are no longer held back",
+            }],
+            |params| {
+                let hold = params.int("hold_wilds_until").max(0) as usize;
+                StrategyFactory::new("Greedy", move || Box::new(Greedy::new(hold)))
+            },
+        ));
+        registry.register(StrategyEntry::new(
+            "defensive",
+            "Saves wild cards for emergencies and sheds high point cards first",
+            vec![
+                ParamSpec {
+                    name: "threat",
+                    kind: ParamKind::Int,
+                    default: "2",
+                    doc: "Opponent hand size at or below which wild cards are played freely",
+                },
+                ParamSpec {
+                    name: "draw_over_wilds",
+                    kind: ParamKind::Bool,
+                    default: "false",
+                    doc: "Draw instead of playing a wild card outside of an emergency",
+                },
+            ],
+            |params| {
+                let threat = params.int("threat").max(0) as usize;
+                let draw_over_wilds = params.bool("draw_over_wilds");
+                StrategyFactory::new("Defensive", move || {
+                    Box::new(Defensive::new(threat, draw_over_wilds))
+                })
+            },
+        ));
+        registry.register(StrategyEntry::new(
+            "aggressive",
+            "Steers its attack cards at the opponent with the fewest cards",
+            Vec::new(),
+            |_| StrategyFactory::new("Aggressive", || Box::<Aggressive>::default()),
+        ));
+        registry.register(StrategyEntry::new(
+            "counting",
+            "Counts unseen cards and plays what the next player is least likely to follow",
+            Vec::new(),
+            |_| StrategyFactory::new("Counting", || Box::<Counting>::default()),
+        ));
+        registry.register(StrategyEntry::new(
+            "heuristic",
+            "Scores every move by a weighted sum of features, see the tune command",
+            vec![
+                ParamSpec {
+                    name: "same_color",
+                    kind: ParamKind::Float,
+                    default: "1.0",
+                    doc: "Weight of the cards left of the color on top after the move",
+                },
+                ParamSpec {
+                    name: "action_value",
+                    kind: ParamKind::Float,
+                    default: "0.5",
+                    doc: "Weight of what the cards played do to the others",
+                },
+                ParamSpec {
+                    name: "next_hand",
+                    kind: ParamKind::Float,
+                    default: "-2.0",
+                    doc: "Weight of one over the hand size of the player moving next",
+                },
+                ParamSpec {
+                    name: "wilds_kept",
+                    kind: ParamKind::Float,
+                    default: "1.0",
+                    doc: "Weight of the wild cards left after the move",
+                },
+                ParamSpec {
+                    name: "draw",
+                    kind: ParamKind::Float,
+                    default: "-5.0",
+                    doc: "Weight of making a move that plays no cards",
+                },
+            ],
+            |params| {
+                let weights = FEATURES.map(|feature| params.float(feature));
+                StrategyFactory::new("Heuristic", move || Box::new(Heuristic::new(weights)))
+            },
+        ));
+        registry.register(StrategyEntry::new(
+            "monte-carlo",
+            "Plays every move out to the end many times and makes the one that wins the most",
+            vec![
+                ParamSpec {
+                    name: "rollouts",
+                    kind: ParamKind::Int,
+                    default: "200",
+                    doc: "How many times every candidate move is played out",
+                },
+                ParamSpec {
+                    name: "time_ms",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "Milliseconds a decision may take before it stops rolling out, 0 for no limit",
+                },
+                ParamSpec {
+                    name: "seed",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "Seeds the deals and rollouts, along with the seed of every game and seat",
+                },
+            ],
+            |params| {
+                let rollouts = params.int("rollouts").max(1) as usize;
+                let budget = match params.int("time_ms") {
+                    ms if ms > 0 => Some(Duration::from_millis(ms as u64)),
+                    _ => None,
+                };
+                let base = params.int("seed") as u64;
+                StrategyFactory::seeded("MonteCarlo", move |seed| {
+                    Box::new(MonteCarlo::new(rollouts, budget, base.wrapping_add(seed)))
+                })
+            },
+        ));
+        registry.register(StrategyEntry::new(
+            "ismcts",
+            "Searches a tree over what it can see, dealing the hidden cards again every iteration",
+            vec![
+                ParamSpec {
+                    name: "iterations",
+                    kind: ParamKind::Int,
+                    default: "1000",
+                    doc: "How many iterations a decision runs, split between the threads",
+                },
+                ParamSpec {
+                    name: "time_ms",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "Milliseconds a decision may take before it stops iterating, 0 for no limit",
+                },
+                ParamSpec {
+                    name: "exploration",
+                    kind: ParamKind::Float,
+                    default: "0.7",
+                    doc: "How much UCB favors rarely tried moves over ones that won before",
+                },
+                ParamSpec {
+                    name: "threads",
+                    kind: ParamKind::Int,
+                    default: "1",
+                    doc: "How many trees are grown at once and merged at the root",
+                },
+                ParamSpec {
+                    name: "seed",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "Seeds the deals and rollouts, along with the seed of every game and seat",
+                },
+            ],
+            |params| {
+                let iterations = params.int("iterations").max(1) as usize;
+                let budget = match params.int("time_ms") {
+                    ms if ms > 0 => Some(Duration::from_millis(ms as u64)),
+                    _ => None,
+                };
+                let exploration = params.float("exploration");
+                let threads = params.int("threads").max(1) as usize;
+                let base = params.int("seed") as u64;
+                StrategyFactory::seeded("ISMCTS", move |seed| {
+                    let seed = base.wrapping_add(seed);
+                    Box::new(Ismcts::new(iterations, budget, exploration, threads, seed))
+                })
+            },
+        ));
+        registry.register(StrategyEntry::wrapping(
+            "endgame-search",
+            "Plays like another strategy until both hands of a two player game are small, then solves the endgame",
+            vec![
+                ParamSpec {
+                    name: "inner",
+                    kind: ParamKind::Text,
+                    default: "greedy",
+                    doc: "The strategy that plays before the endgame, whose parameters are set as inner.<name>",
+                },
+                ParamSpec {
+                    name: "threshold",
+                    kind: ParamKind::Int,
+                    default: "3",
+                    doc: "Hand size at or below which the search takes over",
+                },
+                ParamSpec {
+                    name: "depth",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "How many turns the search looks ahead before estimating by hand sizes, 0 to search exactly",
+                },
+                ParamSpec {
+                    name: "positions",
+                    kind: ParamKind::Int,
+                    default: "100000",
+                    doc: "The most positions an exact search goes through before leaving the move to the inner strategy",
+                },
+                ParamSpec {
+                    name: "deals",
+                    kind: ParamKind::Int,
+                    default: "4",
+                    doc: "How many deals of the hidden cards every decision is averaged over",
+                },
+                ParamSpec {
+                    name: "samples",
+                    kind: ParamKind::Int,
+                    default: "4",
+                    doc: "How many deck shuffles a draw of several cards is averaged over when the depth is limited",
+                },
+                ParamSpec {
+                    name: "seed",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "Seeds the deals and shuffles, along with the seed of every game and seat",
+                },
+            ],
+            |params, inner| {
+                let threshold = params.int("threshold").max(0) as usize;
+                let depth = params.int("depth").max(0) as usize;
+                let positions = params.int("positions").max(1) as usize;
+                let deals = params.int("deals").max(1) as usize;
+                let samples = params.int("samples").max(1) as usize;
+                let base = params.int("seed") as u64;
+                let name = format!("{} + endgame", inner.name);
+                StrategyFactory::seeded(&name, move |seed| {
+                    Box::new(Endgame::new(
+                        inner.build(seed),
+                        threshold,
+                        deals,
+                        match depth {
+                            0 => EndgameSolver::exact(positions, base.wrapping_add(seed)),
+                            _ => EndgameSolver::depth_limited(
+                                depth,
+                                samples,
+                                base.wrapping_add(seed),
+                            ),
+                        },
+                    ))
+                })
+            },
+        ));
+        registry.register(StrategyEntry::fallible(
+            "decision-tree",
+            "Plays by a decision tree read from a JSON or TOML file, see DecisionTree",
+            vec![ParamSpec {
+                name: "file",
+                kind: ParamKind::Text,
+                default: "decision-tree.json",
+                doc: "The tree file, read as TOML if its name ends in .toml",
+            }],
+            |params| {
+                let file = params.text("file");
+                let tree = DecisionTree::load(file).map_err(|err| format!("{}: {}", file, err))?;
+                Ok(tree.factory())
+            },
+        ));
+        registry.register(StrategyEntry::fallible(
+            "q-policy",
+            "Plays the best move of a Q-table trained with the train command",
+            vec![
+                ParamSpec {
+                    name: "file",
+                    kind: ParamKind::Text,
+                    default: "q-policy.txt",
+                    doc: "The policy file written by the train command",
+                },
+                ParamSpec {
+                    name: "seed",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "Seeds the moves made in positions the table never learned",
+                },
+            ],
+            |params| {
+                let file = params.text("file");
+                let table = QTable::load(file).map_err(|err| format!("{}: {}", file, err))?;
+                Ok(policy_factory(table, params.int("seed") as u64))
+            },
+        ));
+        registry.register(StrategyEntry::fallible(
+            "mlp",
+            "Plays the moves of a neural network read from a weights file",
+            vec![
+                ParamSpec {
+                    name: "file",
+                    kind: ParamKind::Text,
+                    default: "mlp.txt",
+                    doc: "The weights file, in the format described by Mlp::load",
+                },
+                ParamSpec {
+                    name: "sample",
+                    kind: ParamKind::Bool,
+                    default: "false",
+                    doc: "Picks moves at random by their chance instead of the most likely one",
+                },
+                ParamSpec {
+                    name: "seed",
+                    kind: ParamKind::Int,
+                    default: "0",
+                    doc: "Seeds the sampling and the moves the network has no index for",
+                },
+            ],
+            |params| {
+                let file = params.text("file");
+                let net = Arc::new(Mlp::load(file).map_err(|err| format!("{}: {}", file, err))?);
+                let sample = params.bool("sample");
+                let base = params.int("seed") as u64;
+                Ok(StrategyFactory::seeded("MLP", move |seed| {
+                    Box::new(MlpPolicy::new(net.clone(), sample, base.wrapping_add(seed)))
+                }))
+            },
+        ));
+        registry.register(StrategyEntry::fallible(
+            "script",
+            "Plays by a Rhai script defining choose(view, moves), see Script",
+            vec![
+                ParamSpec {
+                    name: "file",
+                    kind: ParamKind::Text,
+                    default: "strategy.rhai",
+                    doc: "The script file",
+                },
+                ParamSpec {
+                    name: "max_operations",
+                    kind: ParamKind::Int,
+                    default: "100000",
+                    doc:
+                        "How many operations every call of the script may run before it is stopped",
+                },
+            ],
+            |params| {
+                let file = params.text("file");
+                let max_operations = params.int("max_operations").max(1) as u64;
+                let script = Script::load(file, max_operations)
+                    .map_err(|err| format!("{}: {}", file, err))?;
+                Ok(script.factory())
+            },
+        ));
+        registry
+    }
+
+    /// Adds a strategy, replacing any strategy registered under the same name
+    pub fn register(&mut self, entry: StrategyEntry) {
+        self.entries.retain(|other| other.name != entry.name);
+        self.entries.push(entry);
+    }
+
+    /// Returns the strategy registered under `name`, ignoring case
+    pub fn get(&self, name: &str) -> Option<&StrategyEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns every registered strategy
+    pub fn entries(&self) -> &[StrategyEntry] {
+        &self.entries
+    }
+
+    /// Builds the strategy registered under `name` with the given parameters.
+    /// Parameters are spelled the way config files spell them, and left out parameters take their defaults.
+    pub fn build(
+        &self,
+        name: &str,
+        params: &[(String, String)],
+    ) -> Result<StrategyFactory, StrategyError> {
+        let entry = self
+            .get(name)
+            .ok_or_else(|| StrategyError::UnknownStrategy(name.to_owned()))?;
+        let (inner, params): (Vec<_>, Vec<_>) = match entry.build {
+            Build::Wrapping(_) => params
+                .iter()
+                .cloned()
+                .partition(|(param, _)| param.starts_with(&format!("{}.", INNER))),
+            _ => (Vec::new(), params.to_vec()),
+        };
+        let params = entry.parse_params(&params)?;
+        match entry.build {
+            Build::Infallible(build) => Ok(build(&params)),
+            Build::Fallible(build) => {
+                build(&params).map_err(|reason| StrategyError::Build(name.to_owned(), reason))
+            }
+            Build::Wrapping(build) => {
+                let inner: Vec<(String, String)> = inner
+                    .into_iter()
+                    .map(|(param, value)| (param[INNER.len() + 1..].to_owned(), value))
+                    .collect();
+                let factory = self
+                    .build(params.text(INNER), &inner)
+                    .map_err(|err| StrategyError::Build(name.to_owned(), err.to_string()))?;
+                Ok(build(&params, factory))
+            }
+        }
+    }
+
+    /// Returns the color chooser built into the crate under `name`
+    pub fn color_chooser(&self, name: &str) -> Result<ColorChooserFactory, StrategyError> {
+        color_chooser::by_name(name)
+            .ok_or_else(|| StrategyError::UnknownColorChooser(name.to_owned()))
+    }
+
+    /// Returns the description of every registered strategy and color chooser
+    pub fn describe(&self) -> String {
+        let mut lines: Vec<String> = self.entries.iter().map(StrategyEntry::describe).collect();
+        lines.push(String::new());
+        lines.push("Color choosers:".to_owned());
+        for (name, doc, _) in COLOR_CHOOSERS {
+            lines.push(format!("{:<16}{}", name, doc));
+        }
+        lines.join("\n")
+    }
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn builds_strategies_by_name_ignoring_case() {
+        let registry = StrategyRegistry::new();
+        let factory = registry.build("Greedy", &params(&[])).unwrap();
+        assert_eq!(factory.name, "Greedy");
+    }
+
+    #[test]
+    fn rejects_unknown_strategies() {
+        let err = StrategyRegistry::new()
+            .build("clairvoyant", &params(&[]))
+            .err()
+            .unwrap();
+        assert!(matches!(err, StrategyError::UnknownStrategy(name) if name == "clairvoyant"));
+    }
+
+    #[test]
+    fn rejects_parameters_the_strategy_does_not_take() {
+        let err = StrategyRegistry::new()
+            .build("greedy", &params(&[("threat", "2")]))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            StrategyError::UnknownParam(strategy, param) if strategy == "greedy" && param == "threat"
+        ));
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        let err = StrategyRegistry::new()
+            .build("greedy", &params(&[("hold_wilds_until", "lots")]))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            &err,
+            StrategyError::InvalidParam(param, value, ParamKind::Int)
+                if param == "hold_wilds_until" && value == "lots"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "lots is not a valid value for hold_wilds_until, expected integer"
+        );
+    }
+
+    #[test]
+    fn fills_in_defaults_and_parses_every_kind() {
+        let entry = StrategyEntry::new(
+            "test",
+            "",
+            vec![
+                ParamSpec {
+                    name: "n",
+                    kind: ParamKind::Int,
+                    default: "3",
+                    doc: "",
+                },
+                ParamSpec {
+                    name: "x",
+                    kind: ParamKind::Float,
+                    default: "0.5",
+                    doc: "",
+                },
+                ParamSpec {
+                    name: "flag",
+                    kind: ParamKind::Bool,
+                    default: "false",
+                    doc: "",
+                },
+                ParamSpec {
+                    name: "file",
+                    kind: ParamKind::Text,
+                    default: "a.txt",
+                    doc: "",
+                },
+            ],
+            |_| StrategyFactory::naive(),
+        );
+        let parsed = entry
+            .parse_params(&params(&[("x", "-2"), ("flag", "true")]))
+            .unwrap();
+        assert_eq!(parsed.int("n"), 3);
+        assert_eq!(parsed.float("x"), -2.0);
+        assert!(parsed.bool("flag"));
+        assert_eq!(parsed.text("file"), "a.txt");
+    }
+
+    #[test]
+    fn reports_strategies_that_fail_to_build() {
+        let err = StrategyRegistry::new()
+            .build(
+                "q-policy",
+                &params(&[("file", "/nonexistent/q-policy.txt")]),
+            )
+            .err()
+            .unwrap();
+        assert!(matches!(err, StrategyError::Build(strategy, _) if strategy == "q-policy"));
+    }
+
+    #[test]
+    fn builds_the_endgame_search_around_another_strategy() {
+        let registry = StrategyRegistry::new();
+        let factory = registry
+            .build("endgame-search", &params(&[("inner", "defensive")]))
+            .unwrap();
+        assert_eq!(factory.name, "Defensive + endgame");
+
+        let err = registry
+            .build("endgame-search", &params(&[("inner", "clairvoyant")]))
+            .err()
+            .unwrap();
+        assert!(matches!(err, StrategyError::Build(strategy, _) if strategy == "endgame-search"));
+    }
+
+    #[test]
+    fn builds_the_inner_strategy_from_the_same_registry_with_its_parameters() {
+        let mut registry = StrategyRegistry::new();
+        registry.register(StrategyEntry::new(
+            "stubborn",
+            "",
+            vec![ParamSpec {
+                name: "patience",
+                kind: ParamKind::Int,
+                default: "1",
+                doc: "",
+            }],
+            |_| StrategyFactory::new("Stubborn", || Box::new(crate::algorithms::Naive)),
+        ));
+        let factory = registry
+            .build(
+                "endgame-search",
+                &params(&[("inner", "stubborn"), ("inner.patience", "3")]),
+            )
+            .unwrap();
+        assert_eq!(factory.name, "Stubborn + endgame");
+
+        let err = registry
+            .build(
+                "endgame-search",
+                &params(&[("inner", "stubborn"), ("inner.threat", "2")]),
+            )
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "could not build the endgame-search strategy: the stubborn strategy has no parameter threat"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_color_choosers() {
+        assert!(matches!(
+            StrategyRegistry::new().color_chooser("rainbow"),
+            Err(StrategyError::UnknownColorChooser(name)) if name == "rainbow"
+        ));
+    }
+}
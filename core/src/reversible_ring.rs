@@ -1,5 +1,4 @@
-use std::vec;
-
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum Direction {
     Clockwise,
     CounterClockwise,
@@ -13,7 +12,7 @@ impl Direction {
         }
     }
 
-    fn to_num(&self) -> i32 {
+    fn to_num(self) -> i32 {
         match self {
             Self::Clockwise => 1,
             Self::CounterClockwise => -1,
@@ -21,6 +20,7 @@ impl Direction {
     }
 }
 
+#[derive(Clone)]
 pub struct ReversibleRing<T> {
     vec: Vec<T>,
     index: i32,
@@ -59,14 +59,35 @@ impl<T> ReversibleRing<T> {
         &mut self.vec[self.index as usize]
     }
 
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
     pub fn reverse(&mut self) {
         self.direction = self.direction.get_reverse();
     }
 
+    /// Returns the element the last `next()` or `skip()` landed on
+    pub fn current(&self) -> &T {
+        &self.vec[self.index.max(0) as usize]
+    }
+
+    /// Returns the element `next()` would return, without moving
+    pub fn peek_next(&self) -> &T {
+        let len = self.vec.len() as i32;
+        let index = (self.index + self.direction.to_num()).rem_euclid(len);
+        &self.vec[index as usize]
+    }
+
     pub fn skip(&mut self) {
         self.adjust_index(self.direction.to_num());
     }
 
+    /// Returns the amount of elements in the ring
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
         self.vec.iter_mut()
     }
@@ -1,4 +1,12 @@
-#[derive(PartialEq, Eq, Copy, Clone)]
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::deck::DeckSpec;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
 
 /// The different ways to stack plus cards in Uno
 pub enum PlusStacking {
@@ -16,20 +24,338 @@ pub enum PlusStacking {
     Liberal,
 }
 
-///
-#[derive(Copy, Clone)]
+impl Display for PlusStacking {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Banned => write!(f, "banned"),
+            Self::Conservative => write!(f, "conservative"),
+            Self::Liberal => write!(f, "liberal"),
+        }
+    }
+}
+
+impl FromStr for PlusStacking {
+    type Err = RulesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "banned" => Ok(Self::Banned),
+            "conservative" => Ok(Self::Conservative),
+            "liberal" => Ok(Self::Liberal),
+            _ => Err(RulesError::InvalidValue(
+                "plus_stacking".to_owned(),
+                s.to_owned(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RulesError {
+    UnknownPreset(String),
+    UnknownField(String),
+    InvalidValue(String, String),
+}
+
+impl Display for RulesError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownPreset(name) => write!(
+                f,
+                "unknown rules preset {}, expected one of {}",
+                name,
+                Rules::PRESETS.join(", ")
+            ),
+            Self::UnknownField(field) => write!(
+                f,
+                "unknown rule {}, expected one of {}",
+                field,
+                Rules::FIELDS.join(", ")
+            ),
+            Self::InvalidValue(field, value) => {
+                write!(f, "{} is not a valid value for {}", value, field)
+            }
+        }
+    }
+}
+
+impl Error for RulesError {}
+
+/// The rules a game is played with
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Rules {
     pub plus_stacking: PlusStacking,
-    must_play: bool,
-    draw_until_match: bool,
+
+    /// Whether a player who can play is forbidden from drawing instead
+    pub must_play: bool,
+
+    /// Whether a player who cannot play keeps drawing until they can
+    pub draw_until_match: bool,
+
+    /// Whether a player who draws a playable card may play it right away
+    pub play_drawn: bool,
+
+    /// Whether playing a 7 swaps hands with another player
+    /// and playing a 0 passes every hand along in the direction of play
+    pub seven_zero: bool,
+
+    /// The composition of the deck the game is dealt from.
+    /// Only classic Uno is dealt from a `DeckSpec`.
+    pub deck: DeckSpec,
 }
 
 impl Rules {
-    pub fn default() -> Rules {
+    /// Returns a builder starting from the default rules
+    pub fn builder() -> RulesBuilder {
+        RulesBuilder::new()
+    }
+
+    /// The names of every preset
+    pub const PRESETS: [&'static str; 4] = ["official-2018", "common-house", "competitive", "kids"];
+
+    /// The names of every rule that can be overridden with `Rules::set`
+    pub const FIELDS: [&'static str; 5] = [
+        "plus_stacking",
+        "must_play",
+        "draw_until_match",
+        "play_drawn",
+        "seven_zero",
+    ];
+
+    /// Returns the default rules with a preset applied, see `Rules::apply_preset`
+    pub fn preset(name: &str) -> Result<Rules, RulesError> {
+        let mut rules = Rules::default();
+        rules.apply_preset(name)?;
+        Ok(rules)
+    }
+
+    /// Applies the rules a preset sets on top of these, leaving every other rule as it is,
+    /// so that a variant keeps the defaults the preset says nothing about
+    ///
+    /// * `official-2018` - The rules printed in the 2018 box: no stacking,
+    ///   and a drawn card may be played right away
+    /// * `common-house` - The way most people play: plus cards stack and
+    ///   players draw until they can play
+    /// * `competitive` - Official play with liberal stacking and the 7-0 rule
+    /// * `kids` - Official play, except that anyone who can play has to
+    pub fn apply_preset(&mut self, name: &str) -> Result<(), RulesError> {
+        match name.to_lowercase().as_str() {
+            "official-2018" => {
+                self.plus_stacking = PlusStacking::Banned;
+                self.play_drawn = true;
+            }
+            "common-house" => {
+                self.plus_stacking = PlusStacking::Conservative;
+                self.draw_until_match = true;
+                self.play_drawn = false;
+            }
+            "competitive" => {
+                self.plus_stacking = PlusStacking::Liberal;
+                self.play_drawn = true;
+                self.seven_zero = true;
+            }
+            "kids" => {
+                self.plus_stacking = PlusStacking::Banned;
+                self.play_drawn = true;
+                self.must_play = true;
+            }
+            _ => return Err(RulesError::UnknownPreset(name.to_owned())),
+        }
+        Ok(())
+    }
+
+    /// Overrides a single rule by name, parsing `value` the way the console and config files spell it
+    pub fn set(&mut self, field: &str, value: &str) -> Result<(), RulesError> {
+        let flag = |value: &str| {
+            value
+                .parse::<bool>()
+                .map_err(|_| RulesError::InvalidValue(field.to_owned(), value.to_owned()))
+        };
+        match field {
+            "plus_stacking" => self.plus_stacking = value.parse()?,
+            "must_play" => self.must_play = flag(value)?,
+            "draw_until_match" => self.draw_until_match = flag(value)?,
+            "play_drawn" => self.play_drawn = flag(value)?,
+            "seven_zero" => self.seven_zero = flag(value)?,
+            _ => return Err(RulesError::UnknownField(field.to_owned())),
+        }
+        Ok(())
+    }
+
+    /// Returns a line for every rule describing what it is set to
+    pub fn describe(&self) -> String {
+        let on_off = |enabled: bool| if enabled { "on" } else { "off" };
+        let numbers = self.deck.numbers();
+        [
+            format!("plus_stacking     {}", self.plus_stacking),
+            format!("must_play         {}", on_off(self.must_play)),
+            format!("draw_until_match  {}", on_off(self.draw_until_match)),
+            format!("play_drawn        {}", on_off(self.play_drawn)),
+            format!("seven_zero        {}", on_off(self.seven_zero)),
+            format!(
+                "deck              {} colors, numbers {} to {}, {} cards",
+                self.deck.colors().len(),
+                numbers.start(),
+                numbers.end(),
+                self.deck.size()
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
         Rules {
             plus_stacking: PlusStacking::Conservative,
             must_play: false,
             draw_until_match: false,
+            play_drawn: false,
+            seven_zero: false,
+            deck: DeckSpec::standard(),
+        }
+    }
+}
+
+/// A builder for `Rules`, starting from the defaults or from a preset
+pub struct RulesBuilder {
+    rules: Rules,
+}
+
+impl RulesBuilder {
+    pub fn new() -> Self {
+        Self {
+            rules: Rules::default(),
         }
     }
+
+    /// Starts from the rules bundled under a preset name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of a preset, such as "official-2018" or "kids"
+    pub fn from_preset(name: &str) -> Result<Self, RulesError> {
+        Ok(Self {
+            rules: Rules::preset(name)?,
+        })
+    }
+
+    pub fn plus_stacking(mut self, plus_stacking: PlusStacking) -> Self {
+        self.rules.plus_stacking = plus_stacking;
+        self
+    }
+
+    pub fn must_play(mut self, enabled: bool) -> Self {
+        self.rules.must_play = enabled;
+        self
+    }
+
+    pub fn draw_until_match(mut self, enabled: bool) -> Self {
+        self.rules.draw_until_match = enabled;
+        self
+    }
+
+    pub fn play_drawn(mut self, enabled: bool) -> Self {
+        self.rules.play_drawn = enabled;
+        self
+    }
+
+    pub fn seven_zero(mut self, enabled: bool) -> Self {
+        self.rules.seven_zero = enabled;
+        self
+    }
+
+    pub fn deck(mut self, deck: DeckSpec) -> Self {
+        self.rules.deck = deck;
+        self
+    }
+
+    pub fn build(self) -> Rules {
+        self.rules
+    }
+}
+
+impl Default for RulesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variants::{CrazyEights, Ruleset};
+
+    #[test]
+    fn presets_on_the_default_rules() {
+        let official = Rules::preset("official-2018").unwrap();
+        assert_eq!(official.plus_stacking, PlusStacking::Banned);
+        assert!(official.play_drawn);
+        assert!(!official.must_play && !official.draw_until_match && !official.seven_zero);
+
+        let house = Rules::preset("common-house").unwrap();
+        assert_eq!(house.plus_stacking, PlusStacking::Conservative);
+        assert!(house.draw_until_match && !house.play_drawn);
+
+        let competitive = Rules::preset("Competitive").unwrap();
+        assert_eq!(competitive.plus_stacking, PlusStacking::Liberal);
+        assert!(competitive.seven_zero && competitive.play_drawn);
+
+        let kids = Rules::preset("kids").unwrap();
+        assert!(kids.must_play && kids.play_drawn);
+    }
+
+    #[test]
+    fn unknown_presets_are_rejected() {
+        assert!(matches!(
+            Rules::preset("tournament"),
+            Err(RulesError::UnknownPreset(name)) if name == "tournament"
+        ));
+    }
+
+    #[test]
+    fn presets_keep_the_variant_defaults_they_do_not_set() {
+        let mut rules = CrazyEights.default_rules();
+        rules.apply_preset("kids").unwrap();
+        assert!(rules.draw_until_match);
+        assert!(rules.must_play);
+    }
+
+    #[test]
+    fn set_parses_every_field() {
+        let mut rules = Rules::default();
+        rules.set("plus_stacking", "Liberal").unwrap();
+        rules.set("must_play", "true").unwrap();
+        rules.set("draw_until_match", "true").unwrap();
+        rules.set("play_drawn", "true").unwrap();
+        rules.set("seven_zero", "true").unwrap();
+        assert_eq!(
+            rules,
+            Rules::builder()
+                .plus_stacking(PlusStacking::Liberal)
+                .must_play(true)
+                .draw_until_match(true)
+                .play_drawn(true)
+                .seven_zero(true)
+                .build()
+        );
+    }
+
+    #[test]
+    fn set_rejects_unknown_fields_and_bad_values() {
+        let mut rules = Rules::default();
+        assert!(matches!(
+            rules.set("jump_in", "true"),
+            Err(RulesError::UnknownField(field)) if field == "jump_in"
+        ));
+        assert!(matches!(
+            rules.set("must_play", "yes"),
+            Err(RulesError::InvalidValue(field, value)) if field == "must_play" && value == "yes"
+        ));
+        assert!(matches!(
+            rules.set("plus_stacking", "always"),
+            Err(RulesError::InvalidValue(field, _)) if field == "plus_stacking"
+        ));
+        assert_eq!(rules, Rules::default());
+    }
 }
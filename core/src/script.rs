@@ -0,0 +1,521 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::Path,
+    sync::Arc,
+};
+
+use rhai::{
+    module_resolvers::DummyModuleResolver, Array, CallFnOptions, Dynamic, Engine, EvalAltResult,
+    Map, Scope, AST,
+};
+
+use crate::{
+    action::Action,
+    algorithms::Greedy,
+    card::Card,
+    color::ColorSuite,
+    face::Face,
+    reversible_ring::Direction,
+    rules::Rules,
+    strategy::{Strategy, StrategyFactory},
+    view::PlayerView,
+};
+
+/// The most nested function calls a script may make
+const MAX_CALL_LEVELS: usize = 64;
+
+/// The longest string, array or map a script may build
+const MAX_SIZE: usize = 10_000;
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(io::Error),
+
+    /// The script does not compile, with the line and position of the problem
+    Compile(String),
+
+    /// The script has no `choose` function taking the view and the moves
+    NoChoose,
+
+    /// The script failed or returned something that is not a legal move,
+    /// with the line and position of the problem where there is one
+    Run(String),
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Compile(reason) => write!(f, "{}", reason),
+            Self::NoChoose => write!(f, "the script has no function choose(view, moves)"),
+            Self::Run(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl Error for ScriptError {}
+
+impl From<io::Error> for ScriptError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// A strategy written in Rhai, so that ideas can be tried out without recompiling.
+///
+/// A script defines `fn choose(view, moves)`, which returns the index of one of the `moves`
+/// or one of the moves itself. A move may be returned with its `color` set to declare the
+/// color of a wild card, which is otherwise left up to chance. The script may also define
+/// `fn choose_color(view)`, returning the name of the color of a wild card turned over
+/// before the first turn.
+///
+/// The view is a map of `seat`, `players`, `hand`, `hand_sizes`, `next_seat`, `direction`,
+/// `stack`, `top`, `tops`, `history`, `colors`, `cards_left` and `drawn`, as `PlayerView`
+/// describes them. Every move is a map of its `index`, its `kind`, which is `play`, `place`,
+/// `swap`, `draw` or `pass`, the `cards` it plays, its `target`, the `seat` it swaps with
+/// and whether it `needs_color`. Every card is a map of its `face`, its `number`,
+/// its `color`, whether it is `wild` and the `points` it is worth.
+/// Faces and colors are spelled in lowercase, such as `plus_two` and `red`.
+///
+/// Scripts are sandboxed: they see copies of the game and can't change it,
+/// can't import modules or read files, and every call may only run so many operations.
+/// Only the functions of a script run, never the statements outside them.
+/// Functions may remember things for the rest of the game in the properties of `this`.
+pub struct Script {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    has_choose_color: bool,
+}
+
+impl Script {
+    /// Compiles a script
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the strategy, printed alongside its wins
+    /// * `source` - The text of the script
+    /// * `max_operations` - How many operations every call of the script may run
+    pub fn compile(name: &str, source: &str, max_operations: u64) -> Result<Self, ScriptError> {
+        let mut engine = Engine::new();
+        engine
+            .set_module_resolver(DummyModuleResolver::new())
+            .disable_symbol("eval")
+            .set_max_operations(max_operations)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_string_size(MAX_SIZE)
+            .set_max_array_size(MAX_SIZE)
+            .set_max_map_size(MAX_SIZE)
+            .on_print(|text| log::info!("{}", text))
+            .on_debug(|text, _, pos| log::debug!("{:?}: {}", pos, text));
+        let ast = engine
+            .compile(source)
+            .map_err(|err| ScriptError::Compile(err.to_string()))?;
+
+        let defines = |name: &str, params: usize| {
+            ast.iter_functions()
+                .any(|f| f.name == name && f.params.len() == params)
+        };
+        if !defines("choose", 2) {
+            return Err(ScriptError::NoChoose);
+        }
+        let has_choose_color = defines("choose_color", 1);
+        Ok(Self {
+            name: name.to_owned(),
+            engine,
+            ast,
+            has_choose_color,
+        })
+    }
+
+    /// Reads and compiles a script, named after its file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The script file
+    /// * `max_operations` - How many operations every call of the script may run
+    pub fn load<P: AsRef<Path>>(path: P, max_operations: u64) -> Result<Self, ScriptError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        let name = path
+            .file_stem()
+            .map_or("Script".into(), |stem| stem.to_string_lossy());
+        Self::compile(&name, &source, max_operations)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Calls the script function `name`, with `memory` as `this`.
+    /// Running out of operations has no line, so its error names the limit instead.
+    fn call(
+        &self,
+        memory: &mut Dynamic,
+        name: &str,
+        args: Vec<Dynamic>,
+    ) -> Result<Dynamic, ScriptError> {
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(memory);
+        self.engine
+            .call_fn_with_options(options, &mut Scope::new(), &self.ast, name, args)
+            .map_err(|err| match *err {
+                EvalAltResult::ErrorTooManyOperations(_) => ScriptError::Run(format!(
+                    "{} ran over its limit of {} operations",
+                    name,
+                    self.engine.max_operations()
+                )),
+                err => ScriptError::Run(err.to_string()),
+            })
+    }
+
+    /// Runs the script's `choose` and returns the legal move it picked
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - What the script remembers of the game so far, bound to `this`
+    /// * `moves` - The legal moves
+    /// * `view` - What the player can see
+    pub fn choose(
+        &self,
+        memory: &mut Dynamic,
+        moves: &[Action],
+        view: &PlayerView,
+    ) -> Result<Action, ScriptError> {
+        let listed: Array = moves
+            .iter()
+            .enumerate()
+            .map(|(i, action)| move_map(i, action))
+            .collect();
+        let picked = self.call(memory, "choose", vec![view_map(view), listed.into()])?;
+
+        let (index, color) = if let Ok(index) = picked.as_int() {
+            (index, None)
+        } else if let Some(picked) = picked.clone().try_cast::<Map>() {
+            let index = picked
+                .get("index")
+                .and_then(|index| index.as_int().ok())
+                .ok_or_else(|| {
+                    ScriptError::Run("the move choose returned has no index".to_owned())
+                })?;
+            let color = match picked.get("color") {
+                Some(color) if !color.is_unit() => Some(color_named(color, view)?),
+                _ => None,
+            };
+            (index, color)
+        } else {
+            return Err(ScriptError::Run(format!(
+                "choose must return a move or its index, not {}",
+                picked.type_name()
+            )));
+        };
+
+        let mut action = usize::try_from(index)
+            .ok()
+            .and_then(|i| moves.get(i))
+            .cloned()
+            .ok_or_else(|| {
+                ScriptError::Run(format!(
+                    "choose returned the index {} of {} moves",
+                    index,
+                    moves.len()
+                ))
+            })?;
+        if action.cards().iter().any(Card::is_wild) {
+            if let Action::Play {
+                color: declared, ..
+            }
+            | Action::Place {
+                color: declared, ..
+            } = &mut action
+            {
+                *declared = color;
+            }
+        }
+        Ok(action)
+    }
+
+    /// Runs the script's `choose_color`, if it has one
+    pub fn choose_color(
+        &self,
+        memory: &mut Dynamic,
+        view: &PlayerView,
+    ) -> Result<Option<ColorSuite>, ScriptError> {
+        if !self.has_choose_color {
+            return Ok(None);
+        }
+        let color = self.call(memory, "choose_color", vec![view_map(view)])?;
+        color_named(&color, view).map(Some)
+    }
+
+    /// Returns a factory playing this script in every game
+    pub fn factory(self) -> StrategyFactory {
+        let script = Arc::new(self);
+        StrategyFactory::new(&script.name.clone(), move || {
+            Box::new(ScriptPolicy::new(script.clone()))
+        })
+    }
+}
+
+/// Reads a color name returned by a script, which has to be one of the game's colors
+fn color_named(color: &Dynamic, view: &PlayerView) -> Result<ColorSuite, ScriptError> {
+    color
+        .clone()
+        .into_string()
+        .ok()
+        .and_then(|name| ColorSuite::named(&name))
+        .filter(|color| view.colors().contains(color))
+        .ok_or_else(|| ScriptError::Run(format!("{} is not a color of this game", color)))
+}
+
+fn card_map(card: &Card) -> Dynamic {
+    let (face, number) = match card.face {
+        Face::Number(n) => ("number", Dynamic::from_int(n.into())),
+        Face::Rank(n) => ("rank", Dynamic::from_int(n.into())),
+        Face::Skip => ("skip", Dynamic::UNIT),
+        Face::Reverse => ("reverse", Dynamic::UNIT),
+        Face::PlusTwo => ("plus_two", Dynamic::UNIT),
+        Face::Wild => ("wild", Dynamic::UNIT),
+        Face::PlusFour => ("plus_four", Dynamic::UNIT),
+    };
+    let mut map = Map::new();
+    map.insert("face".into(), face.into());
+    map.insert("number".into(), number);
+    map.insert(
+        "color".into(),
+        card.color_suite()
+            .map_or(Dynamic::UNIT, |color| color.name().into()),
+    );
+    map.insert("wild".into(), card.is_wild().into());
+    map.insert("points".into(), (card.points() as i64).into());
+    map.into()
+}
+
+fn cards_array<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Dynamic {
+    cards.into_iter().map(card_map).collect::<Array>().into()
+}
+
+fn ints_array(values: &[usize]) -> Dynamic {
+    values
+        .iter()
+        .map(|&value| Dynamic::from_int(value as i64))
+        .collect::<Array>()
+        .into()
+}
+
+fn move_map(index: usize, action: &Action) -> Dynamic {
+    let kind = match action {
+        Action::Play { .. } => "play",
+        Action::Place { .. } => "place",
+        Action::Swap { .. } => "swap",
+        Action::Draw => "draw",
+        _ => "pass",
+    };
+    let mut map = Map::new();
+    map.insert("index".into(), (index as i64).into());
+    map.insert("kind".into(), kind.into());
+    map.insert("cards".into(), cards_array(action.cards()));
+    map.insert(
+        "needs_color".into(),
+        action.cards().iter().any(Card::is_wild).into(),
+    );
+    match action {
+        Action::Play { target, .. } => {
+            map.insert("target".into(), (*target as i64).into());
+        }
+        Action::Swap { seat } => {
+            map.insert("seat".into(), (*seat as i64).into());
+        }
+        _ => {}
+    }
+    map.into()
+}
+
+fn view_map(view: &PlayerView) -> Dynamic {
+    let direction = match view.direction() {
+        Direction::Clockwise => "clockwise",
+        Direction::CounterClockwise => "counter_clockwise",
+    };
+    let colors: Array = view
+        .colors()
+        .iter()
+        .map(|color| color.name().into())
+        .collect();
+    let mut map = Map::new();
+    map.insert("seat".into(), (view.seat() as i64).into());
+    map.insert("players".into(), (view.players() as i64).into());
+    map.insert("hand".into(), cards_array(view.hand()));
+    map.insert("hand_sizes".into(), ints_array(view.hand_sizes()));
+    map.insert("next_seat".into(), (view.next_seat() as i64).into());
+    map.insert("direction".into(), direction.into());
+    map.insert("stack".into(), (view.stack_count() as i64).into());
+    map.insert("top".into(), card_map(view.top()));
+    map.insert("tops".into(), cards_array(view.tops()));
+    map.insert("history".into(), cards_array(view.history()));
+    map.insert("colors".into(), colors.into());
+    map.insert("cards_left".into(), (view.cards_left() as i64).into());
+    map.insert("drawn".into(), (view.drawn() as i64).into());
+    map.into()
+}
+
+/// Plays the moves of a `Script`.
+/// Whenever the script fails, the error is logged along with the line it failed on
+/// and the decision is made like `Greedy` instead.
+pub struct ScriptPolicy {
+    script: Arc<Script>,
+
+    /// What the script remembers of the current game, bound to `this`
+    memory: Dynamic,
+
+    fallback: Greedy,
+}
+
+impl ScriptPolicy {
+    /// # Arguments
+    ///
+    /// * `script` - The script, which may be shared between games
+    pub fn new(script: Arc<Script>) -> Self {
+        Self {
+            script,
+            memory: Map::new().into(),
+            fallback: Greedy::new(2),
+        }
+    }
+}
+
+impl Strategy for ScriptPolicy {
+    fn name(&self) -> &str {
+        self.script.name()
+    }
+
+    fn on_game_start(&mut self, _seat: usize, _rules: &Rules) {
+        self.memory = Map::new().into();
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        match self.script.choose(&mut self.memory, moves, view) {
+            Ok(action) => action,
+            Err(err) => {
+                log::warn!("the {} script failed: {}", self.script.name(), err);
+                self.fallback.choose(moves, view)
+            }
+        }
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        match self.script.choose_color(&mut self.memory, view) {
+            Ok(color) => color,
+            Err(err) => {
+                log::warn!("the {} script failed: {}", self.script.name(), err);
+                self.fallback.choose_color(view)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::GameState,
+        variants::{Classic, Ruleset},
+    };
+
+    fn started_game() -> GameState {
+        let rules = Classic.default_rules();
+        let mut state = GameState::new(
+            2,
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(3),
+        );
+        Classic.setup(&mut state);
+        state
+    }
+
+    /// Runs the `choose` of `source` on the first move of a game
+    fn run(source: &str, max_operations: u64) -> Result<Action, ScriptError> {
+        let script = Script::compile("test", source, max_operations)?;
+        let state = started_game();
+        let moves = Classic.legal_moves(&state, 0);
+        let view = PlayerView::new(&Classic, &state, 0);
+        script.choose(&mut Map::new().into(), &moves, &view)
+    }
+
+    fn error(source: &str) -> String {
+        run(source, 10_000).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn plays_the_move_the_script_picks() {
+        let state = started_game();
+        let moves = Classic.legal_moves(&state, 0);
+        let last = run("fn choose(view, moves) { moves.len() - 1 }", 10_000).unwrap();
+        assert_eq!(last, moves[moves.len() - 1]);
+        let first = run("fn choose(view, moves) { moves[0] }", 10_000).unwrap();
+        assert_eq!(first, moves[0]);
+    }
+
+    #[test]
+    fn reports_where_a_script_fails() {
+        assert_eq!(
+            error("fn choose(view, moves) {\n  let x = ;\n}"),
+            "Unexpected ';' (line 2, position 11)"
+        );
+        assert_eq!(
+            error("fn choose(view, moves) {\n  let x = 1;\n  foo(x)\n}"),
+            "Function not found: foo (i64) (line 3, position 3)"
+        );
+        assert!(matches!(
+            run("fn pick(view, moves) { 0 }", 10_000),
+            Err(ScriptError::NoChoose)
+        ));
+        let count = Classic.legal_moves(&started_game(), 0).len();
+        assert_eq!(
+            error("fn choose(view, moves) { moves.len() + 5 }"),
+            format!("choose returned the index {} of {} moves", count + 5, count)
+        );
+        assert_eq!(
+            error("fn choose(view, moves) { \"first\" }"),
+            "choose must return a move or its index, not string"
+        );
+    }
+
+    #[test]
+    fn rejects_colors_the_game_is_not_played_with() {
+        let script = Script::compile(
+            "suits",
+            "fn choose(view, moves) { 0 }\nfn choose_color(view) { \"hearts\" }",
+            10_000,
+        )
+        .unwrap();
+        let state = started_game();
+        let view = PlayerView::new(&Classic, &state, 0);
+        let err = script
+            .choose_color(&mut Map::new().into(), &view)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "hearts is not a color of this game");
+    }
+
+    #[test]
+    fn stops_scripts_that_run_too_long() {
+        let err = run("fn choose(view, moves) { loop {} }", 500).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "choose ran over its limit of 500 operations"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_greedy_when_the_script_fails() {
+        let script = Script::compile("broken", "fn choose(view, moves) { foo() }", 10_000).unwrap();
+        let mut policy = ScriptPolicy::new(Arc::new(script));
+        let state = started_game();
+        let moves = Classic.legal_moves(&state, 0);
+        let view = PlayerView::new(&Classic, &state, 0);
+        let action = policy.choose(&moves, &view);
+        assert!(moves.iter().any(|legal| legal.same_move(&action)));
+    }
+}
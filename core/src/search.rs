@@ -0,0 +1,435 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    action::Action, card::Card, color::ColorSuite, hand_analysis::CardHistogram,
+    strategy::Strategy, view::PlayerView,
+};
+
+/// Rollouts that run longer than this many turns count as a loss
+const MAX_ROLLOUT_TURNS: usize = 1000;
+
+/// Plays a random card whenever it can, like `Naive`, but takes its randomness from `rng`
+pub fn rollout_move<R: Rng + ?Sized>(moves: &[Action], rng: &mut R) -> Action {
+    let plays: Vec<&Action> = moves.iter().filter(|action| action.is_play()).collect();
+    match plays.choose(rng) {
+        Some(&action) => action.clone(),
+        None => moves[0].clone(),
+    }
+}
+
+/// Returns every move out of `moves`, with plays of wild cards repeated once for every color
+pub fn with_colors(moves: &[Action], colors: &[ColorSuite]) -> Vec<Action> {
+    let mut candidates = Vec::with_capacity(moves.len());
+    for action in moves {
+        if !action.cards().iter().any(Card::is_wild) {
+            candidates.push(action.clone());
+            continue;
+        }
+        for &declared in colors {
+            let mut action = action.clone();
+            if let Action::Play { color, .. } | Action::Place { color, .. } = &mut action {
+                *color = Some(declared);
+            }
+            candidates.push(action);
+        }
+    }
+    candidates
+}
+
+/// Plays every candidate move out to the end of the game many times
+/// and makes the one that won the most.
+/// Every rollout deals the cards it can't see at random and lets every seat
+/// play like `Naive` from there on.
+pub struct MonteCarlo {
+    /// How many times every candidate move is played out
+    rollouts: usize,
+
+    /// How long a single decision may take, if that is limited
+    budget: Option<Duration>,
+
+    rng: SmallRng,
+}
+
+impl MonteCarlo {
+    /// # Arguments
+    ///
+    /// * `rollouts` - How many times every candidate move is played out
+    /// * `budget` - How long a single decision may take, if that is limited
+    /// * `seed` - Seeds every deal and rollout, so that the same game is played the same way
+    pub fn new(rollouts: usize, budget: Option<Duration>, seed: u64) -> Self {
+        Self {
+            rollouts: rollouts.max(1),
+            budget,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for MonteCarlo {
+    fn name(&self) -> &str {
+        "MonteCarlo"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let candidates = with_colors(moves, view.colors());
+        if candidates.len() == 1 {
+            return candidates[0].clone();
+        }
+
+        let start = Instant::now();
+        let mut wins = vec![0; candidates.len()];
+        let mut rounds = 0;
+        while rounds < self.rollouts {
+            // Every candidate is played out from the same deal with the same luck,
+            // so that only the candidate itself makes a difference
+            let deal = view.determinize(&mut self.rng);
+            let seed = self.rng.gen();
+            for (action, wins) in candidates.iter().zip(wins.iter_mut()) {
+                let mut simulation = deal.clone();
+                simulation.apply(action.clone());
+                let mut rng = SmallRng::seed_from_u64(seed);
+                let winner = simulation
+                    .play_out(|moves, _| rollout_move(moves, &mut rng), MAX_ROLLOUT_TURNS);
+                if winner == Some(view.seat()) {
+                    *wins += 1;
+                }
+            }
+            rounds += 1;
+            if self.budget.is_some_and(|budget| start.elapsed() >= budget) {
+                break;
+            }
+        }
+
+        for (action, wins) in candidates.iter().zip(wins.iter()) {
+            log::debug!(
+                "seat {} won {}/{} rollouts after {:?}",
+                view.seat(),
+                wins,
+                rounds,
+                action
+            );
+        }
+        // Ties go to the move listed first
+        let best = (0..candidates.len())
+            .max_by_key(|&i| (wins[i], std::cmp::Reverse(i)))
+            .unwrap();
+        candidates[best].clone()
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        match CardHistogram::new(view.hand()).max() {
+            Some((color, _)) => Some(color),
+            None => Some(ColorSuite::random_with(view.colors(), &mut self.rng)),
+        }
+    }
+}
+
+/// A node of an ISMCTS tree, standing for every game state the searching player
+/// can't tell apart after the same moves
+struct Node {
+    /// The move that leads here from the parent, `None` at the root
+    action: Option<Action>,
+
+    /// The seat that made `action`
+    seat: usize,
+
+    children: Vec<usize>,
+
+    /// The iterations that went through this node
+    visits: u32,
+
+    /// The iterations through this node that `seat` went on to win
+    wins: u32,
+
+    /// The iterations where this node's move was legal, whether or not it was picked
+    available: u32,
+}
+
+impl Node {
+    fn new(action: Option<Action>, seat: usize) -> Self {
+        Self {
+            action,
+            seat,
+            children: Vec::new(),
+            visits: 0,
+            wins: 0,
+            available: 1,
+        }
+    }
+
+    /// Rates this node for selection, higher is better
+    fn ucb(&self, exploration: f64) -> f64 {
+        let visits = self.visits as f64;
+        self.wins as f64 / visits + exploration * ((self.available as f64).ln() / visits).sqrt()
+    }
+}
+
+/// A single ISMCTS tree, grown from the view of the player who is searching
+struct Tree {
+    /// Every node, with the root first
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn new(seat: usize) -> Self {
+        Self {
+            nodes: vec![Node::new(None, seat)],
+        }
+    }
+
+    /// Grows the tree until `iterations` iterations ran or `deadline` passed
+    fn grow<R: Rng>(
+        &mut self,
+        view: &PlayerView,
+        iterations: usize,
+        deadline: Option<Instant>,
+        exploration: f64,
+        rng: &mut R,
+    ) {
+        for _ in 0..iterations {
+            self.iterate(view, exploration, rng);
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+        }
+    }
+
+    /// Deals the hidden cards again and walks a single path through the tree,
+    /// adding a node at its end and playing the game out from there
+    fn iterate<R: Rng>(&mut self, view: &PlayerView, exploration: f64, rng: &mut R) {
+        let mut simulation = view.determinize(rng);
+        let mut node = 0;
+        let mut path = vec![0];
+        while simulation.winner().is_none() {
+            let moves = with_colors(&simulation.legal_moves(), simulation.colors());
+            let children = self.nodes[node].children.clone();
+            let mut untried = moves.clone();
+            let mut legal = Vec::with_capacity(children.len());
+            for child in children {
+                let action = self.nodes[child].action.as_ref().unwrap();
+                if moves.contains(action) {
+                    untried.retain(|other| other != action);
+                    self.nodes[child].available += 1;
+                    legal.push(child);
+                }
+            }
+
+            if let Some(action) = untried.choose(rng).cloned() {
+                let child = self.nodes.len();
+                self.nodes
+                    .push(Node::new(Some(action.clone()), simulation.seat()));
+                self.nodes[node].children.push(child);
+                simulation.apply(action);
+                path.push(child);
+                break;
+            }
+
+            node = *legal
+                .iter()
+                .max_by(|&&a, &&b| {
+                    let a = self.nodes[a].ucb(exploration);
+                    a.total_cmp(&self.nodes[b].ucb(exploration))
+                })
+                .unwrap();
+            simulation.apply(self.nodes[node].action.clone().unwrap());
+            path.push(node);
+        }
+
+        let winner = simulation.play_out(|moves, _| rollout_move(moves, rng), MAX_ROLLOUT_TURNS);
+        for node in path {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            if winner == Some(node.seat) {
+                node.wins += 1;
+            }
+        }
+    }
+
+    /// Returns the moves at the root along with their visits and wins
+    fn root_moves(&self) -> Vec<(Action, u32, u32)> {
+        self.nodes[0]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &self.nodes[child];
+                (node.action.clone().unwrap(), node.visits, node.wins)
+            })
+            .collect()
+    }
+}
+
+/// Information Set Monte Carlo Tree Search.
+/// Grows a single tree over what the player can see, dealing the hidden cards
+/// again on every iteration, picks moves by UCB and makes the most visited one.
+/// Trees can be grown on several threads at once and merged at the root.
+pub struct Ismcts {
+    /// How many iterations a decision runs, split between the threads
+    iterations: usize,
+
+    /// How long a single decision may take, if that is limited
+    budget: Option<Duration>,
+
+    /// How much UCB favors rarely tried moves over ones that won before
+    exploration: f64,
+
+    /// How many trees are grown at once
+    threads: usize,
+
+    rng: SmallRng,
+}
+
+impl Ismcts {
+    /// # Arguments
+    ///
+    /// * `iterations` - How many iterations a decision runs, split between the threads
+    /// * `budget` - How long a single decision may take, if that is limited
+    /// * `exploration` - The UCB exploration constant
+    /// * `threads` - How many trees are grown at once
+    /// * `seed` - Seeds every deal and rollout, so that the same game is played the same way
+    pub fn new(
+        iterations: usize,
+        budget: Option<Duration>,
+        exploration: f64,
+        threads: usize,
+        seed: u64,
+    ) -> Self {
+        Self {
+            iterations: iterations.max(1),
+            budget,
+            exploration,
+            threads: threads.max(1),
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for Ismcts {
+    fn name(&self) -> &str {
+        "ISMCTS"
+    }
+
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action {
+        let candidates = with_colors(moves, view.colors());
+        if candidates.len() == 1 {
+            return candidates[0].clone();
+        }
+
+        let deadline = self.budget.map(|budget| Instant::now() + budget);
+        let seeds: Vec<u64> = (0..self.threads).map(|_| self.rng.gen()).collect();
+        let (iterations, exploration) = (self.iterations, self.exploration);
+        let threads = self.threads;
+        let trees: Vec<Tree> = thread::scope(|scope| {
+            let handles: Vec<_> = seeds
+                .into_iter()
+                .enumerate()
+                .map(|(i, seed)| {
+                    // The first trees pick up what is left after splitting the iterations
+                    let share = iterations / threads + usize::from(i < iterations % threads);
+                    scope.spawn(move || {
+                        let mut rng = SmallRng::seed_from_u64(seed);
+                        let mut tree = Tree::new(view.seat());
+                        tree.grow(view, share, deadline, exploration, &mut rng);
+                        tree
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        // Merges the root of every tree, keeping the order the moves were first tried in
+        let mut merged: Vec<(Action, u32, u32)> = Vec::new();
+        for (action, visits, wins) in trees.iter().flat_map(Tree::root_moves) {
+            match merged.iter_mut().find(|(other, _, _)| *other == action) {
+                Some((_, total_visits, total_wins)) => {
+                    *total_visits += visits;
+                    *total_wins += wins;
+                }
+                None => merged.push((action, visits, wins)),
+            }
+        }
+        for (action, visits, wins) in merged.iter() {
+            log::debug!(
+                "seat {} visited {:?} {} times, winning {}",
+                view.seat(),
+                action,
+                visits,
+                wins
+            );
+        }
+        merged
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, visits, _)| *visits)
+            .map(|(action, _, _)| action)
+            .unwrap_or_else(|| candidates[0].clone())
+    }
+
+    fn choose_color(&mut self, view: &PlayerView) -> Option<ColorSuite> {
+        match CardHistogram::new(view.hand()).max() {
+            Some((color, _)) => Some(color),
+            None => Some(ColorSuite::random_with(view.colors(), &mut self.rng)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        face::Face,
+        state::GameState,
+        variants::{Classic, Ruleset},
+    };
+
+    fn red(face: Face) -> Card {
+        Card::new_colored(face, ColorSuite::Red)
+    }
+
+    /// Two players where seat 0 is in turn with a red skip and a red 5 on a red 7,
+    /// so skipping the opponent and going out right after is the only sure win
+    fn skip_to_win() -> GameState {
+        let rules = Classic.default_rules();
+        let mut state = GameState::new(
+            2,
+            rules,
+            Classic.cards(&rules),
+            Classic.colors(&rules),
+            Some(4),
+        );
+        state.hands[0].add_many(vec![red(Face::Skip), red(Face::Number(5))]);
+        state.hands[1].add_card(Card::new_colored(Face::Number(1), ColorSuite::Blue));
+        state.table.add_target(red(Face::Number(7)));
+        state.order.next();
+        state
+    }
+
+    fn choose(ismcts: &mut Ismcts, state: &GameState) -> Action {
+        let moves = Classic.legal_moves(state, 0);
+        ismcts.choose(&moves, &PlayerView::new(&Classic, state, 0))
+    }
+
+    #[test]
+    fn ismcts_finds_the_only_sure_win() {
+        let state = skip_to_win();
+        let mut ismcts = Ismcts::new(400, None, 0.7, 2, 11);
+        assert_eq!(choose(&mut ismcts, &state), Action::play(red(Face::Skip)));
+    }
+
+    #[test]
+    fn ismcts_makes_the_same_move_from_the_same_seed() {
+        let state = skip_to_win();
+        for seed in 0..8 {
+            let first = choose(&mut Ismcts::new(6, None, 0.7, 2, seed), &state);
+            let second = choose(&mut Ismcts::new(6, None, 0.7, 2, seed), &state);
+            assert_eq!(first, second);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    deck::DeckSpec,
+    rules::{Rules, RulesError},
+    variants::{self, Classic, Ruleset, UnknownVariant},
+};
+
+/// The game to play and the rules to play it by,
+/// shared by `GameMasterBuilder` and `UnoEnvBuilder`
+#[derive(Clone, Copy)]
+pub struct GameSetup {
+    ruleset: &'static dyn Ruleset,
+    rules: Rules,
+}
+
+impl GameSetup {
+    /// Classic Uno with the default rules
+    pub fn new() -> Self {
+        Self {
+            ruleset: &Classic,
+            rules: Rules::default(),
+        }
+    }
+
+    /// The `Ruleset` that runs every game
+    pub fn ruleset(&self) -> &'static dyn Ruleset {
+        self.ruleset
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Sets the rules to be used during the games
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - A `Rules` struct
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Applies a named preset on top of the current rules,
+    /// so the variant's defaults stay for every rule the preset leaves alone
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of a preset, such as "official-2018" or "kids"
+    pub fn with_preset(mut self, name: &str) -> Result<Self, RulesError> {
+        self.rules.apply_preset(name)?;
+        Ok(self)
+    }
+
+    /// Overrides a single rule by name
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The name of the rule, such as "plus_stacking"
+    /// * `value` - The value to set it to, such as "liberal" or "true"
+    pub fn with_rule(mut self, field: &str, value: &str) -> Result<Self, RulesError> {
+        self.rules.set(field, value)?;
+        Ok(self)
+    }
+
+    /// Selects the game to play by name, resetting the rules to that game's defaults
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of a ruleset, such as "uno", "crazy-eights" or "dos"
+    pub fn with_variant(self, name: &str) -> Result<Self, UnknownVariant> {
+        let ruleset = variants::by_name(name).ok_or_else(|| UnknownVariant(name.to_owned()))?;
+        Ok(self.with_ruleset(ruleset))
+    }
+
+    /// Sets the game to play, resetting the rules to that game's defaults
+    ///
+    /// # Arguments
+    ///
+    /// * `ruleset` - The `Ruleset` that runs every game
+    pub fn with_ruleset(mut self, ruleset: &'static dyn Ruleset) -> Self {
+        self.ruleset = ruleset;
+        self.rules = ruleset.default_rules();
+        self
+    }
+
+    /// Sets the composition of the deck the games are dealt from
+    ///
+    /// # Arguments
+    ///
+    /// * `deck` - A `DeckSpec` describing the colors and numbers in the deck
+    pub fn with_deck(mut self, deck: DeckSpec) -> Self {
+        self.rules.deck = deck;
+        self
+    }
+}
+
+impl Default for GameSetup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
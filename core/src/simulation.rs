@@ -0,0 +1,232 @@
+use crate::{
+    action::Action, card::Card, card_counter::as_dealt, color::ColorSuite, driver::TurnDriver,
+    reversible_ring::Direction, rules::Rules, state::GameState, variants::Ruleset,
+    view::PlayerView,
+};
+
+/// Everything that decides how a simulated game goes on from here,
+/// so that positions reached in different ways can be recognized
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PositionKey {
+    seat: usize,
+    direction: Direction,
+    hands: Vec<Vec<Card>>,
+    tops: Vec<Card>,
+    /// The deck in sorted order, since the order is up to chance
+    deck: Vec<Card>,
+    stack_count: usize,
+    /// Whether the seat in turn played any cards so far
+    turn_played: bool,
+    stalled_turns: usize,
+    drawn: usize,
+    played: Vec<usize>,
+    placements: usize,
+    placed: Vec<Card>,
+    penalty: usize,
+    swapping: bool,
+}
+
+/// A private copy of a game that a strategy can play forward to see where its moves lead.
+/// Obtained from `PlayerView::determinize`, so the cards the player can't see
+/// are dealt at random and it shows only one way the game could go.
+///
+/// Turns are taken by a `TurnDriver` like in a real game, but no events are published.
+pub struct Simulation {
+    /// Has no strategies, so it hands every move back to the simulation
+    driver: TurnDriver,
+}
+
+impl Clone for Simulation {
+    fn clone(&self) -> Self {
+        Self {
+            driver: self.driver.fork(),
+        }
+    }
+}
+
+impl Simulation {
+    pub(crate) fn new(ruleset: &'static dyn Ruleset, state: GameState, seat: usize) -> Self {
+        let mut driver = TurnDriver::resume(ruleset, state, seat);
+        driver.advance();
+        Self { driver }
+    }
+
+    fn state(&self) -> &GameState {
+        self.driver.state()
+    }
+
+    /// The seat whose turn is in progress, or the seat that moved last once the game is over
+    pub fn seat(&self) -> usize {
+        self.driver
+            .seat()
+            .unwrap_or_else(|| *self.state().order.current())
+    }
+
+    /// The seat that won, if the game is over
+    pub fn winner(&self) -> Option<usize> {
+        self.driver.winner()
+    }
+
+    /// The amount of turns finished in this simulation
+    pub fn turns(&self) -> usize {
+        self.driver.turns()
+    }
+
+    /// Returns every move the seat in turn may make, or nothing once the game is over
+    pub fn legal_moves(&self) -> Vec<Action> {
+        match self.driver.seat() {
+            Some(seat) if self.winner().is_none() => {
+                self.driver.ruleset().legal_moves(self.state(), seat)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.state().rules
+    }
+
+    /// The colors wild cards can be declared as
+    pub fn colors(&self) -> &'static [ColorSuite] {
+        self.state().colors
+    }
+
+    /// The amount of cards in every hand, indexed by seat
+    pub fn hand_sizes(&self) -> Vec<usize> {
+        self.state().hands.iter().map(|hand| hand.len()).collect()
+    }
+
+    /// The amount of cards left in the deck
+    pub fn cards_left(&self) -> usize {
+        self.state().dealer.cards_left()
+    }
+
+    /// Returns every distinct card in the deck along with how many copies it holds
+    pub(crate) fn deck_counts(&self) -> Vec<(Card, usize)> {
+        let mut deck = self.state().dealer.cards().to_vec();
+        deck.sort();
+        let mut counts: Vec<(Card, usize)> = Vec::new();
+        for card in deck {
+            match counts.last_mut() {
+                Some((last, count)) if *last == card => *count += 1,
+                _ => counts.push((card, 1)),
+            }
+        }
+        counts
+    }
+
+    /// Moves a copy of each of `cards` to the top of the deck, so that they are drawn first,
+    /// in the order they are listed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the deck does not hold `cards`
+    pub(crate) fn draw_first(&mut self, cards: &[Card]) {
+        let mut deck = self.state().dealer.cards().to_vec();
+        let mut top = Vec::with_capacity(cards.len());
+        for card in cards {
+            let i = deck
+                .iter()
+                .rposition(|other| other == card)
+                .unwrap_or_else(|| panic!("the deck does not hold {}", card));
+            top.push(deck.remove(i));
+        }
+        // The last card of the deck is drawn first
+        deck.extend(top.into_iter().rev());
+        self.driver.state_mut().dealer.replace_cards(deck);
+    }
+
+    /// Puts the cards cleared off the table under the deck, as if the deck had run out
+    /// and been reshuffled, so that every card that can be drawn is in the deck
+    pub(crate) fn reshuffle(&mut self) {
+        let state = self.driver.state_mut();
+        let mut deck: Vec<Card> = state
+            .table
+            .collect_discards()
+            .iter()
+            .map(as_dealt)
+            .collect();
+        deck.extend_from_slice(state.dealer.cards());
+        state.dealer.replace_cards(deck);
+    }
+
+    /// The amount of cards dealt from the deck so far
+    pub(crate) fn taken(&self) -> usize {
+        self.state().dealer.taken()
+    }
+
+    /// Shuffles the deck in an order decided by `seed` alone
+    pub(crate) fn shuffle_deck(&mut self, seed: u64) {
+        let state = self.driver.state_mut();
+        state.reseed(seed);
+        state.dealer.shuffle();
+    }
+
+    /// Returns what decides how the game goes on from here
+    pub(crate) fn key(&self) -> PositionKey {
+        let mut deck = self.state().dealer.cards().to_vec();
+        deck.sort();
+        let turn = &self.state().turn;
+        PositionKey {
+            seat: self.seat(),
+            direction: self.state().order.direction(),
+            hands: self
+                .state()
+                .hands
+                .iter()
+                .map(|hand| {
+                    let mut cards: Vec<Card> = hand.into_iter().copied().collect();
+                    cards.sort();
+                    cards
+                })
+                .collect(),
+            tops: self.state().table.tops(),
+            deck,
+            stack_count: self.state().stack_count,
+            turn_played: self.driver.played(),
+            stalled_turns: self.driver.stalled_turns(),
+            drawn: turn.drawn,
+            played: turn.played.clone(),
+            placements: turn.placements,
+            placed: turn.placed.clone(),
+            penalty: turn.penalty,
+            swapping: turn.swapping,
+        }
+    }
+
+    /// What the seat in turn can see of the simulated game
+    pub fn view(&self) -> PlayerView<'_> {
+        PlayerView::new(self.driver.ruleset(), self.state(), self.seat())
+    }
+
+    /// Carries out a move of the seat in turn, moving on to the next seat with a move to make
+    /// once the turn is over
+    ///
+    /// # Panics
+    ///
+    /// Panics if the game is over
+    pub fn apply(&mut self, action: Action) {
+        if self.winner().is_some() {
+            panic!(
+                "tried to make the move {:?} after the game was over",
+                action
+            );
+        }
+        self.driver.apply(action);
+        self.driver.advance();
+    }
+
+    /// Lets `policy` make every move until the game is over or `max_turns` turns went by.
+    /// Returns the winner, if there is one by then.
+    pub fn play_out<F>(&mut self, mut policy: F, max_turns: usize) -> Option<usize>
+    where
+        F: FnMut(&[Action], &PlayerView) -> Action,
+    {
+        while self.winner().is_none() && self.turns() < max_turns {
+            let moves = self.legal_moves();
+            let action = policy(&moves, &self.view());
+            self.apply(action);
+        }
+        self.winner()
+    }
+}
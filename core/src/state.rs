@@ -0,0 +1,295 @@
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    card::Card,
+    color::ColorSuite,
+    deck::{Deck, DeckError},
+    event::GameEvent,
+    hand::Hand,
+    reversible_ring::ReversibleRing,
+    rules::Rules,
+    table::Table,
+};
+
+/// Handles dealing operations using a `Deck`
+#[derive(Clone)]
+pub struct Dealer {
+    deck: Deck,
+
+    /// The amount of cards dealt from the deck so far, across every reshuffle
+    taken: usize,
+}
+
+impl Dealer {
+    /// Constructs a new `Dealer` with the given `Deck`
+    pub fn new(deck: Deck) -> Self {
+        Self { deck, taken: 0 }
+    }
+
+    pub fn deal_many(&mut self, hand: &mut Hand, n: usize) -> Result<(), DeckError> {
+        let cards = self.deck.take_many(n)?;
+        self.taken += cards.len();
+        hand.add_many(cards);
+        Ok(())
+    }
+
+    /// Shuffles the cards on the table back into the deck and returns how many there were
+    pub fn reshuffle(&mut self, table: &mut Table) -> usize {
+        let cards = table.collect_discards();
+        let count = cards.len();
+        self.deck.replace_cards(cards);
+        self.deck.shuffle();
+        self.deck.re_wild();
+        count
+    }
+
+    pub fn shuffle(&mut self) {
+        self.deck.shuffle()
+    }
+
+    /// Makes every following shuffle depend on `seed` alone
+    pub fn reseed(&mut self, seed: u64) {
+        self.deck.reseed(seed)
+    }
+
+    /// Returns the cards in the deck. The last card is the top of the deck.
+    pub fn cards(&self) -> &[Card] {
+        self.deck.cards()
+    }
+
+    /// Replaces the deck with `cards`, keeping their order
+    pub fn replace_cards(&mut self, cards: Vec<Card>) {
+        self.deck.replace_cards(cards)
+    }
+
+    pub fn take(&mut self) -> Result<Card, DeckError> {
+        let card = self.deck.take()?;
+        self.taken += 1;
+        Ok(card)
+    }
+
+    pub fn peek(&self) -> Result<&Card, DeckError> {
+        self.deck.peek()
+    }
+
+    /// Returns the amount of cards left in the deck
+    pub fn cards_left(&self) -> usize {
+        self.deck.len()
+    }
+
+    /// Returns the amount of cards dealt from the deck so far, across every reshuffle
+    pub fn taken(&self) -> usize {
+        self.taken
+    }
+}
+
+/// Bookkeeping for the turn in progress.
+/// It is reset before every turn.
+#[derive(Clone, Default)]
+pub struct TurnState {
+    /// Cards the player has drawn so far this turn
+    pub drawn: usize,
+
+    /// Indices of the targets played onto this turn
+    pub played: Vec<usize>,
+
+    /// Cards the player still has to lay down as new targets
+    pub placements: usize,
+
+    /// Cards laid down this turn, which become targets once the turn ends
+    pub placed: Vec<Card>,
+
+    /// Cards every other player draws once the turn ends
+    pub penalty: usize,
+
+    /// Whether the player still has to pick someone to swap hands with
+    pub swapping: bool,
+}
+
+/// Everything on the table during a game, without the players' strategies.
+/// Rulesets read and change the game through this.
+#[derive(Clone)]
+pub struct GameState {
+    pub rules: Rules,
+
+    /// The colors wild cards can be declared as
+    pub colors: &'static [ColorSuite],
+
+    /// Every card the game is played with, as dealt
+    pub composition: Vec<Card>,
+
+    /// Each seat's hand
+    pub hands: Vec<Hand>,
+    pub dealer: Dealer,
+    pub table: Table,
+
+    /// The order seats take their turns in
+    pub order: ReversibleRing<usize>,
+
+    /// The amount of cards stacked up by plus cards for the next player to draw
+    pub stack_count: usize,
+    pub turn: TurnState,
+
+    /// Decides every random color, so that a seeded game plays out the same every time
+    rng: SmallRng,
+
+    /// Events that happened since the game last published them
+    events: Vec<GameEvent>,
+}
+
+impl GameState {
+    /// Constructs the state of a game that is about to be set up.
+    /// Shuffling and random colors depend on `seed` alone if there is one.
+    pub fn new(
+        seats: usize,
+        rules: Rules,
+        cards: Vec<Card>,
+        colors: &'static [ColorSuite],
+        seed: Option<u64>,
+    ) -> Self {
+        let mut rng = match seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::from_entropy(),
+        };
+        let mut deck = Deck::from_cards(cards.clone());
+        deck.reseed(rng.gen());
+        deck.shuffle();
+        Self {
+            rules,
+            colors,
+            composition: cards,
+            hands: vec![Hand::new(); seats],
+            dealer: Dealer::new(deck),
+            table: Table::new(),
+            order: ReversibleRing::from_iter(0..seats),
+            stack_count: 0,
+            turn: TurnState::default(),
+            rng,
+            events: Vec::new(),
+        }
+    }
+
+    /// Makes every shuffle and random color from now on depend on `seed` alone
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self.dealer.reseed(self.rng.gen());
+    }
+
+    /// Deals `n` cards to every seat
+    pub fn deal_hands(&mut self, n: usize) {
+        for (seat, hand) in self.hands.iter_mut().enumerate() {
+            self.dealer
+                .deal_many(hand, n)
+                .unwrap_or_else(|err| panic!("error during initial deal: {}", err));
+            self.events.push(GameEvent::Dealt { seat, count: n });
+        }
+    }
+
+    /// Records that `event` happened
+    pub fn emit(&mut self, event: GameEvent) {
+        self.events.push(event)
+    }
+
+    /// Records that `seat` played `cards` onto `target`,
+    /// along with the colors declared for any wild cards among them
+    pub fn emit_played(&mut self, seat: usize, target: usize, cards: Vec<Card>) {
+        let colors: Vec<ColorSuite> = cards
+            .iter()
+            .filter(|card| card.is_wild())
+            .filter_map(Card::color_suite)
+            .collect();
+        self.emit(GameEvent::Played {
+            seat,
+            target,
+            cards,
+        });
+        for color in colors {
+            self.emit(GameEvent::ColorChosen { seat, color });
+        }
+    }
+
+    /// Removes and returns every event recorded so far
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Takes the top card of the deck, reshuffling the table back into the deck if it is empty.
+    /// Returns `None` when neither the deck nor the table has any cards left.
+    fn take_reshuffling(&mut self) -> Option<Card> {
+        if self.dealer.cards_left() == 0 {
+            let cards = self.dealer.reshuffle(&mut self.table);
+            self.emit(GameEvent::Reshuffled { cards });
+        }
+        self.dealer.take().ok()
+    }
+
+    /// Deals up to `n` cards to `seat`, reshuffling when the deck runs out,
+    /// and returns how many were dealt
+    pub fn draw(&mut self, seat: usize, n: usize) -> usize {
+        let mut dealt = 0;
+        while dealt < n {
+            let Some(card) = self.take_reshuffling() else {
+                break;
+            };
+            self.hands[seat].add_card(card);
+            dealt += 1;
+        }
+        self.turn.drawn += dealt;
+        if dealt > 0 {
+            self.emit(GameEvent::Drew { seat, count: dealt });
+        }
+        dealt
+    }
+
+    /// Turns the top card of the deck onto the table as a new target,
+    /// giving wild cards a random color.
+    /// Returns false when there are no cards left to flip.
+    pub fn flip(&mut self) -> bool {
+        let Some(mut card) = self.take_reshuffling() else {
+            return false;
+        };
+        self.declare(&mut card, None);
+        self.table.add_target(card);
+        self.emit(GameEvent::Flipped { card });
+        true
+    }
+
+    /// Gives `card` a color if it is a wild card without one.
+    /// The declared color is used if there is one, otherwise the color is random.
+    pub fn declare(&mut self, card: &mut Card, color: Option<ColorSuite>) {
+        if card.is_wild() && card.color_suite().is_none() {
+            card.assign_color(
+                color.unwrap_or_else(|| ColorSuite::random_with(self.colors, &mut self.rng)),
+            )
+        }
+    }
+
+    /// Returns the indices of the targets topped by a wild card without a color
+    pub fn undeclared_targets(&self) -> Vec<usize> {
+        let tops = self.table.tops();
+        (0..tops.len())
+            .filter(|&i| tops[i].color_suite().is_none())
+            .collect()
+    }
+
+    /// Lets `seat` declare the color of the wild card on top of `target`.
+    /// The declared color is used if there is one, otherwise the color is random.
+    pub fn declare_target(&mut self, seat: usize, target: usize, color: Option<ColorSuite>) {
+        let color = color.unwrap_or_else(|| ColorSuite::random_with(self.colors, &mut self.rng));
+        self.table.declare(target, color);
+        self.emit(GameEvent::ColorChosen { seat, color });
+    }
+
+    /// Removes `card` from the hand of `seat` and declares its color
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hand does not hold `card`
+    pub fn take_from_hand(&mut self, seat: usize, card: &Card, color: Option<ColorSuite>) -> Card {
+        let mut card = self.hands[seat]
+            .remove_card(card)
+            .unwrap_or_else(|| panic!("seat {} does not hold {}", seat, card));
+        self.declare(&mut card, color);
+        card
+    }
+}
@@ -1,53 +1,99 @@
-use crate::{algorithms::naive, card::Card, hand::Hand, player::PlayResult, rules::Rules};
+use std::sync::Arc;
 
-use std::{
-    hash::{self, Hash},
-    ptr,
+use crate::{
+    action::Action,
+    algorithms::Naive,
+    color::ColorSuite,
+    color_chooser::{ColorChooserFactory, WithColors},
+    event::GameEvent,
+    rules::Rules,
+    view::PlayerView,
 };
 
-#[derive(Clone)]
+/// Decides how a player plays.
+/// Every game gets its own instance, so a strategy may remember
+/// whatever it likes between turns.
+pub trait Strategy: Send {
+    fn name(&self) -> &str;
+
+    /// Called once before the first card is dealt
+    fn on_game_start(&mut self, _seat: usize, _rules: &Rules) {}
+
+    /// Picks one of the legal `moves` given what the player can see.
+    /// `moves` always holds at least one move.
+    fn choose(&mut self, moves: &[Action], view: &PlayerView) -> Action;
+
+    /// Picks the color of a wild card turned over before the first turn.
+    /// `None` leaves the color up to chance.
+    fn choose_color(&mut self, _view: &PlayerView) -> Option<ColorSuite> {
+        None
+    }
 
-pub struct Strategy {
+    /// Called for every event of the game, including the player's own moves
+    fn on_event(&mut self, _event: &GameEvent) {}
+
+    /// Called once the game has a winner
+    fn on_game_end(&mut self, _won: bool) {}
+}
+
+/// Builds a fresh `Strategy` for every game
+#[derive(Clone)]
+pub struct StrategyFactory {
     pub name: String,
-    algorithm: fn(&Card, &mut Hand, &Rules, bool) -> PlayResult,
+    build: Arc<dyn Fn(u64) -> Box<dyn Strategy> + Send + Sync>,
 }
 
-impl Strategy {
-    pub fn naive() -> Self {
-        Self {
-            name: "Naive".to_owned(),
-            algorithm: naive,
-        }
+impl StrategyFactory {
+    /// # Arguments
+    ///
+    /// * `name` - The name of the strategy, printed alongside its wins
+    /// * `build` - A closure returning a new instance of the strategy
+    pub fn new<F>(name: &str, build: F) -> Self
+    where
+        F: Fn() -> Box<dyn Strategy> + Send + Sync + 'static,
+    {
+        Self::seeded(name, move |_| build())
     }
 
-    pub fn get(name: String) -> Self {
-        match name.to_lowercase().as_str() {
-            "naive" => Self::naive(),
-            _ => panic!("unknown strategy {}", name),
+    /// Like `new`, for strategies that make random decisions
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the strategy, printed alongside its wins
+    /// * `build` - A closure returning a new instance of the strategy,
+    ///   given a seed that only depends on the game and the seat it plays in
+    pub fn seeded<F>(name: &str, build: F) -> Self
+    where
+        F: Fn(u64) -> Box<dyn Strategy> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_owned(),
+            build: Arc::new(build),
         }
     }
 
-    pub fn run(
-        &self,
-        card: &Card,
-        hand: &mut Hand,
-        rules: &Rules,
-        plus_stacking: bool,
-    ) -> PlayResult {
-        (self.algorithm)(card, hand, rules, plus_stacking)
+    pub fn naive() -> Self {
+        Self::new("Naive", || Box::new(Naive))
     }
-}
 
-impl Eq for Strategy {}
-
-impl PartialEq for Strategy {
-    fn eq(&self, other: &Self) -> bool {
-        ptr::eq(self.algorithm as *const (), other.algorithm as *const ())
+    /// Pairs this strategy with a chooser that declares the colors of its wild cards
+    ///
+    /// # Arguments
+    ///
+    /// * `chooser` - Builds a fresh `ColorChooser` for every game
+    pub fn with_colors(self, chooser: ColorChooserFactory) -> Self {
+        let name = format!("{} + {}", self.name, chooser().name());
+        Self::seeded(&name, move |seed| {
+            Box::new(WithColors::new(self.build(seed), chooser()))
+        })
     }
-}
 
-impl Hash for Strategy {
-    fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        ptr::hash(self.algorithm as *const (), state);
+    /// Returns a new instance of the strategy, ready for a game
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seeds any random decisions, see `GameOptions::strategy_seed`
+    pub fn build(&self, seed: u64) -> Box<dyn Strategy> {
+        (self.build)(seed)
     }
 }
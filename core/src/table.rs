@@ -0,0 +1,95 @@
+use crate::{card::Card, color::ColorSuite, pile::Pile};
+
+/// The cards lying face up on the table
+#[derive(Clone)]
+pub struct Table {
+    /// The piles that cards are played onto.
+    /// Uno and Crazy Eights have a single discard pile while Dos has a row of center cards.
+    targets: Vec<Pile>,
+
+    /// Cards cleared off the table that are waiting to be reshuffled into the deck
+    discarded: Vec<Card>,
+
+    /// Every card that was ever laid face up, in order
+    history: Vec<Card>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+            discarded: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Returns the top card of the first target.
+    /// This is the discard pile in single pile games.
+    pub fn top(&self) -> &Card {
+        self.targets[0].top()
+    }
+
+    /// Returns the top card of every target
+    pub fn tops(&self) -> Vec<Card> {
+        self.targets.iter().map(|pile| *pile.top()).collect()
+    }
+
+    /// Declares the color of the wild card on top of the target at index `target`
+    pub fn declare(&mut self, target: usize, color: ColorSuite) {
+        self.targets[target].top_mut().assign_color(color)
+    }
+
+    /// Returns the amount of targets on the table
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Returns every card that is face up on the table,
+    /// including the cards waiting to be reshuffled into the deck
+    pub fn face_up(&self) -> Vec<Card> {
+        let mut cards = self.discarded.clone();
+        for pile in self.targets.iter() {
+            cards.extend_from_slice(pile.cards());
+        }
+        cards
+    }
+
+    /// Returns every card that was ever laid face up, oldest first
+    pub fn history(&self) -> &[Card] {
+        &self.history
+    }
+
+    /// Lays `card` down as a new target
+    pub fn add_target(&mut self, card: Card) {
+        self.history.push(card);
+        let mut pile = Pile::new();
+        pile.add(card);
+        self.targets.push(pile);
+    }
+
+    /// Plays `card` onto the target at index `target`
+    pub fn play(&mut self, target: usize, card: Card) {
+        self.history.push(card);
+        self.targets[target].add(card)
+    }
+
+    /// Removes the targets at the given indices and discards all of their cards
+    pub fn clear_targets(&mut self, mut indices: Vec<usize>) {
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        indices.dedup();
+        for i in indices {
+            let pile = self.targets.remove(i);
+            self.discarded.extend(pile.into_cards());
+        }
+    }
+
+    /// Removes every card except the top card of each target and returns them,
+    /// along with everything that was already discarded.
+    pub fn collect_discards(&mut self) -> Vec<Card> {
+        let mut cards = std::mem::take(&mut self.discarded);
+        for pile in self.targets.iter_mut() {
+            cards.append(&mut pile.reduce_to_top());
+        }
+        cards
+    }
+}
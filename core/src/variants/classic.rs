@@ -0,0 +1,181 @@
+use crate::{
+    action::Action, card::Card, color::ColorSuite, event::GameEvent, face::Face,
+    reversible_ring::Direction, rules::Rules, state::GameState,
+};
+
+use super::{Ruleset, TurnStatus};
+
+/// Classic Uno, dealt from the rules' `DeckSpec`
+pub struct Classic;
+
+impl Ruleset for Classic {
+    fn name(&self) -> &'static str {
+        "uno"
+    }
+
+    fn cards(&self, rules: &Rules) -> Vec<Card> {
+        rules.deck.cards()
+    }
+
+    fn colors(&self, rules: &Rules) -> &'static [ColorSuite] {
+        rules.deck.colors()
+    }
+
+    fn setup(&self, state: &mut GameState) {
+        const UNO_HAND_SIZE: usize = 7;
+        state.deal_hands(UNO_HAND_SIZE);
+        // the game must not start on a plus four (https://www.unorules.com/)
+        start_pile(state, |card| card.face == Face::PlusFour);
+    }
+
+    fn legal_moves(&self, state: &GameState, seat: usize) -> Vec<Action> {
+        single_pile_moves(state, seat)
+    }
+
+    fn apply(&self, state: &mut GameState, seat: usize, action: Action) -> TurnStatus {
+        apply_single_pile(state, seat, action)
+    }
+}
+
+/// Flips the first card of the deck onto the discard pile.
+/// Cards that `must_bury` rejects are shuffled back into the deck,
+/// and a wild card is left for the first player to declare.
+pub(super) fn start_pile(state: &mut GameState, must_bury: fn(&Card) -> bool) {
+    loop {
+        let top = state
+            .dealer
+            .peek()
+            .unwrap_or_else(|err| panic!("error pulling card for pile initialization: {}", err));
+        if must_bury(top) {
+            state.dealer.shuffle();
+            continue;
+        }
+
+        // We can safely unwrap here because we peeked earlier
+        let top = state.dealer.take().unwrap();
+        state.table.add_target(top);
+        state.emit(GameEvent::Flipped { card: top });
+        break;
+    }
+}
+
+/// Returns the legal moves of a game played on a single discard pile
+pub(super) fn single_pile_moves(state: &GameState, seat: usize) -> Vec<Action> {
+    if state.turn.swapping {
+        return (0..state.hands.len())
+            .filter(|&other| other != seat)
+            .map(|other| Action::Swap { seat: other })
+            .collect();
+    }
+
+    let hand = &state.hands[seat];
+    let top = state.table.top();
+    let drawn = state.turn.drawn > 0;
+    let playable = if state.stack_count > 0 {
+        hand.all_plus_stackable(top, &state.rules.plus_stacking)
+    } else if drawn && !state.rules.draw_until_match {
+        // Only the card that was just drawn may be played
+        let last = hand.len() - 1;
+        if hand.get(last).stacks_on(top) {
+            vec![last]
+        } else {
+            vec![]
+        }
+    } else {
+        hand.all_stackable_indices(top)
+    };
+    let mut moves: Vec<Action> = playable
+        .into_iter()
+        .map(|i| Action::play(*hand.get(i)))
+        .collect();
+    if drawn {
+        moves.push(Action::Pass);
+    } else if moves.is_empty() || state.stack_count > 0 || !state.rules.must_play {
+        moves.push(Action::Draw);
+    }
+    moves
+}
+
+/// Carries out the effect of a card that was just played
+fn resolve_card(state: &mut GameState, seat: usize, card: Card) -> TurnStatus {
+    match card.face {
+        Face::Reverse => {
+            state.order.reverse();
+            let direction = state.order.direction();
+            state.emit(GameEvent::Reversed { seat, direction });
+        }
+        Face::Skip => {
+            state.order.skip();
+            let skipped = *state.order.current();
+            state.emit(GameEvent::Skipped { seat: skipped });
+        }
+        Face::PlusTwo | Face::PlusFour => state.stack_count += card.plus_stack_value(),
+        Face::Number(7) if state.rules.seven_zero => {
+            state.turn.swapping = true;
+            return TurnStatus::Continue;
+        }
+        // A player going out on a 0 keeps their empty hand and wins
+        Face::Number(0) if state.rules.seven_zero && !state.hands[seat].is_empty() => {
+            let direction = state.order.direction();
+            match direction {
+                Direction::Clockwise => state.hands.rotate_right(1),
+                Direction::CounterClockwise => state.hands.rotate_left(1),
+            }
+            state.emit(GameEvent::HandsRotated { direction });
+        }
+        _ => {}
+    }
+    TurnStatus::Over
+}
+
+/// Carries out a move in a game played on a single discard pile
+pub(super) fn apply_single_pile(state: &mut GameState, seat: usize, action: Action) -> TurnStatus {
+    match action {
+        Action::Play { cards, color, .. } => {
+            let card = state.take_from_hand(seat, &cards[0], color);
+            state.table.play(0, card);
+            state.emit_played(seat, 0, vec![card]);
+            resolve_card(state, seat, card)
+        }
+        Action::Swap { seat: other } => {
+            state.hands.swap(seat, other);
+            state.turn.swapping = false;
+            state.emit(GameEvent::HandsSwapped { seat, other });
+            TurnStatus::Over
+        }
+        Action::Draw if state.stack_count > 0 => {
+            let stack = state.stack_count;
+            let count = state.draw(seat, stack);
+            state.stack_count = 0;
+            state.emit(GameEvent::StackResolved { seat, count });
+            TurnStatus::Over
+        }
+        Action::Draw if state.rules.draw_until_match => {
+            // Draws at least once, even when the player chose to draw over a playable card
+            loop {
+                if state.draw(seat, 1) == 0 {
+                    return TurnStatus::Over;
+                }
+                let top = state.table.top();
+                if !state.hands[seat].all_stackable_indices(top).is_empty() {
+                    return TurnStatus::Continue;
+                }
+            }
+        }
+        Action::Draw if state.rules.play_drawn => {
+            if state.draw(seat, 1) == 0 {
+                return TurnStatus::Over;
+            }
+            TurnStatus::Continue
+        }
+        Action::Draw => {
+            state.draw(seat, 1);
+            TurnStatus::Over
+        }
+        Action::Pass => {
+            state.emit(GameEvent::Passed { seat });
+            TurnStatus::Over
+        }
+        Action::Place { .. } => unreachable!("single pile games have no center row"),
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    action::Action,
+    card::Card,
+    color::ColorSuite,
+    face::Face,
+    rules::{PlusStacking, Rules},
+    state::GameState,
+};
+
+use super::{
+    classic::{apply_single_pile, single_pile_moves, start_pile},
+    Ruleset, TurnStatus,
+};
+
+/// Crazy Eights on a standard 52 card deck.
+/// Cards match by rank or suit, eights are wild with a declared suit,
+/// there are no action cards and players draw until they can play.
+pub struct CrazyEights;
+
+impl Ruleset for CrazyEights {
+    fn name(&self) -> &'static str {
+        "crazy-eights"
+    }
+
+    fn default_rules(&self) -> Rules {
+        Rules {
+            plus_stacking: PlusStacking::Banned,
+            draw_until_match: true,
+            ..Rules::default()
+        }
+    }
+
+    /// The suit printed on an eight never matters, so eights are dealt as wild cards.
+    fn cards(&self, _rules: &Rules) -> Vec<Card> {
+        const CRAZY_EIGHT: u8 = 8;
+        let mut cards = Vec::with_capacity(52);
+        for &suit in ColorSuite::suits() {
+            for rank in 1..=13 {
+                if rank == CRAZY_EIGHT {
+                    cards.push(Card::new_wild(Face::Rank(rank)));
+                } else {
+                    cards.push(Card::new_colored(Face::Rank(rank), suit));
+                }
+            }
+        }
+        cards
+    }
+
+    fn colors(&self, _rules: &Rules) -> &'static [ColorSuite] {
+        ColorSuite::suits()
+    }
+
+    fn setup(&self, state: &mut GameState) {
+        let hand_size = if state.hands.len() == 2 { 7 } else { 5 };
+        state.deal_hands(hand_size);
+        // the game must not start on an eight
+        start_pile(state, Card::is_wild);
+    }
+
+    fn legal_moves(&self, state: &GameState, seat: usize) -> Vec<Action> {
+        single_pile_moves(state, seat)
+    }
+
+    fn apply(&self, state: &mut GameState, seat: usize, action: Action) -> TurnStatus {
+        apply_single_pile(state, seat, action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::GameEvent, table::Table};
+
+    fn spades(rank: u8) -> Card {
+        Card::new_colored(Face::Rank(rank), ColorSuite::Spades)
+    }
+
+    fn hearts(rank: u8) -> Card {
+        Card::new_colored(Face::Rank(rank), ColorSuite::Hearts)
+    }
+
+    #[test]
+    fn an_eight_plays_on_anything_and_names_a_suit() {
+        let rules = CrazyEights.default_rules();
+        let cards = CrazyEights.cards(&rules);
+        let eight = Card::new_wild(Face::Rank(8));
+        assert_eq!(cards.iter().filter(|&&card| card == eight).count(), 4);
+
+        let mut state = GameState::new(2, rules, cards, CrazyEights.colors(&rules), Some(3));
+        state.hands[0].add_many(vec![eight, hearts(3)]);
+        state.table = Table::new();
+        state.table.add_target(spades(12));
+        assert_eq!(
+            CrazyEights.legal_moves(&state, 0),
+            vec![Action::play(eight), Action::Draw]
+        );
+
+        let play = Action::Play {
+            target: 0,
+            cards: vec![eight],
+            color: Some(ColorSuite::Hearts),
+        };
+        assert_eq!(CrazyEights.apply(&mut state, 0, play), TurnStatus::Over);
+        assert_eq!(state.table.top().color_suite(), Some(ColorSuite::Hearts));
+        assert!(state.take_events().contains(&GameEvent::ColorChosen {
+            seat: 0,
+            color: ColorSuite::Hearts,
+        }));
+        assert!(hearts(3).stacks_on(state.table.top()));
+    }
+}
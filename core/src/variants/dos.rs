@@ -0,0 +1,366 @@
+use crate::{
+    action::Action,
+    card::Card,
+    color::ColorSuite,
+    event::GameEvent,
+    face::Face,
+    hand::Hand,
+    rules::{PlusStacking, Rules},
+    state::GameState,
+};
+
+use super::{Ruleset, TurnStatus};
+
+/// Dos, played on a row of center cards.
+/// Cards match a center card by number, alone or as two cards summing to it,
+/// and also matching its color earns bonuses.
+/// A player who can't match even after drawing adds a card to the center row instead.
+pub struct Dos;
+
+impl Dos {
+    /// The amount of center cards the row is refilled to after every turn
+    const ROW_SIZE: usize = 2;
+
+    /// Deals center cards until the row is full again
+    fn refill_center_row(state: &mut GameState) {
+        while state.table.len() < Self::ROW_SIZE && state.flip() {}
+    }
+}
+
+impl Ruleset for Dos {
+    fn name(&self) -> &'static str {
+        "dos"
+    }
+
+    fn default_rules(&self) -> Rules {
+        Rules {
+            plus_stacking: PlusStacking::Banned,
+            ..Rules::default()
+        }
+    }
+
+    /// Every color has three each of 1, 3, 4 and 5 and two each of 6 through 10,
+    /// and there are twelve Wild Dos cards that count as a 2 of any color.
+    /// The Wild # cards of the retail deck are left out.
+    fn cards(&self, _rules: &Rules) -> Vec<Card> {
+        const WILD_DOS: u8 = 2;
+        let mut cards = Vec::with_capacity(100);
+        for &color in ColorSuite::standard() {
+            for n in (1..=10).filter(|&n| n != WILD_DOS) {
+                let copies = if n <= 5 { 3 } else { 2 };
+                for _ in 0..copies {
+                    cards.push(Card::new_colored(Face::Number(n), color));
+                }
+            }
+        }
+        for _ in 0..12 {
+            cards.push(Card::new_wild(Face::Number(WILD_DOS)));
+        }
+        cards
+    }
+
+    fn colors(&self, _rules: &Rules) -> &'static [ColorSuite] {
+        ColorSuite::standard()
+    }
+
+    fn setup(&self, state: &mut GameState) {
+        const DOS_HAND_SIZE: usize = 7;
+        state.deal_hands(DOS_HAND_SIZE);
+        Self::refill_center_row(state);
+    }
+
+    fn legal_moves(&self, state: &GameState, seat: usize) -> Vec<Action> {
+        let hand = &state.hands[seat];
+        let turn = &state.turn;
+        if turn.placements > 0 && !hand.is_empty() {
+            return hand
+                .into_iter()
+                .map(|&card| Action::Place { card, color: None })
+                .collect();
+        }
+        let mut moves = Vec::new();
+        for (target, top) in state.table.tops().iter().enumerate() {
+            if turn.played.contains(&target) {
+                continue;
+            }
+            for indices in matches(hand, top) {
+                moves.push(Action::Play {
+                    target,
+                    cards: indices.into_iter().map(|i| *hand.get(i)).collect(),
+                    color: None,
+                });
+            }
+        }
+        if turn.played.is_empty() && turn.drawn == 0 {
+            if moves.is_empty() || !state.rules.must_play {
+                moves.push(Action::Draw);
+            }
+        } else {
+            moves.push(Action::Pass);
+        }
+        moves
+    }
+
+    fn apply(&self, state: &mut GameState, seat: usize, action: Action) -> TurnStatus {
+        match action {
+            Action::Play {
+                target,
+                cards,
+                color,
+            } => {
+                let top = state.table.tops()[target];
+                let played: Vec<Card> = cards
+                    .iter()
+                    .map(|card| state.take_from_hand(seat, card, color))
+                    .collect();
+                let bonus = color_bonus(&top, &played);
+                state.turn.placements += bonus.placements();
+                if bonus == ColorBonus::Double {
+                    state.turn.penalty += 1;
+                }
+                for &card in played.iter() {
+                    state.table.play(target, card);
+                }
+                state.emit_played(seat, target, played);
+                state.turn.played.push(target);
+                TurnStatus::Continue
+            }
+            Action::Place { card, color } => {
+                let card = state.take_from_hand(seat, &card, color);
+                state.turn.placements -= 1;
+                state.turn.placed.push(card);
+                state.emit(GameEvent::Placed { seat, card });
+                if let (true, Some(color)) = (card.is_wild(), card.color_suite()) {
+                    state.emit(GameEvent::ColorChosen { seat, color });
+                }
+                TurnStatus::Continue
+            }
+            Action::Draw => {
+                // A player that cannot match draws one card and may match with it
+                if state.draw(seat, 1) == 0 {
+                    return TurnStatus::Over;
+                }
+                // Otherwise they have to add a card from their hand to the center row
+                let hand = &state.hands[seat];
+                if state
+                    .table
+                    .tops()
+                    .iter()
+                    .all(|top| matches(hand, top).is_empty())
+                {
+                    state.turn.placements += 1;
+                }
+                TurnStatus::Continue
+            }
+            Action::Pass => {
+                if state.turn.played.is_empty() && state.turn.placed.is_empty() {
+                    state.emit(GameEvent::Passed { seat });
+                }
+                TurnStatus::Over
+            }
+            Action::Swap { .. } => unreachable!("Dos has no hand swaps"),
+        }
+    }
+
+    fn end_turn(&self, state: &mut GameState, seat: usize) {
+        let played = std::mem::take(&mut state.turn.played);
+        state.table.clear_targets(played);
+        for card in std::mem::take(&mut state.turn.placed) {
+            state.table.add_target(card);
+        }
+        Self::refill_center_row(state);
+
+        let penalty = state.turn.penalty;
+        for other in (0..state.hands.len()).filter(|&other| other != seat) {
+            state.draw(other, penalty);
+        }
+    }
+}
+
+/// The extra cards a Dos match earns by also matching the target's color
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ColorBonus {
+    None,
+
+    /// One card matched the target's number and color.
+    /// The player adds a card from their hand to the center row.
+    Single,
+
+    /// Two cards summed to the target's number and both matched its color.
+    /// The player adds a card to the center row and every other player draws a card.
+    Double,
+}
+
+impl ColorBonus {
+    /// Returns how many cards the player gets to add to the center row
+    pub fn placements(&self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Single | Self::Double => 1,
+        }
+    }
+}
+
+/// Returns the number a card counts as in Dos
+fn number(card: &Card) -> Option<u8> {
+    match card.face {
+        Face::Number(n) => Some(n),
+        _ => None,
+    }
+}
+
+/// Returns every way to match `target` with cards from `hand`, as sets of hand indices.
+/// A match is either a single card with the same number
+/// or two cards whose numbers sum to the target's number.
+pub fn matches(hand: &Hand, target: &Card) -> Vec<Vec<usize>> {
+    let Some(goal) = number(target) else {
+        return Vec::new();
+    };
+    let numbers: Vec<Option<u8>> = hand.into_iter().map(number).collect();
+    let mut found = Vec::new();
+    for (i, a) in numbers.iter().enumerate() {
+        let Some(a) = a else { continue };
+        if *a == goal {
+            found.push(vec![i]);
+        }
+        for (j, b) in numbers.iter().enumerate().skip(i + 1) {
+            if matches!(b, Some(b) if a + b == goal) {
+                found.push(vec![i, j]);
+            }
+        }
+    }
+    found
+}
+
+/// Returns the bonus earned by matching `target` with `cards`.
+/// Wild cards must have their color declared to count as a color match.
+pub fn color_bonus(target: &Card, cards: &[Card]) -> ColorBonus {
+    let target_color = target.color_suite();
+    let all_match =
+        target_color.is_some() && cards.iter().all(|card| card.color_suite() == target_color);
+    match (all_match, cards.len()) {
+        (true, 1) => ColorBonus::Single,
+        (true, 2) => ColorBonus::Double,
+        _ => ColorBonus::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::Table;
+
+    fn red(n: u8) -> Card {
+        Card::new_colored(Face::Number(n), ColorSuite::Red)
+    }
+
+    fn blue(n: u8) -> Card {
+        Card::new_colored(Face::Number(n), ColorSuite::Blue)
+    }
+
+    fn hand_of(cards: &[Card]) -> Hand {
+        let mut hand = Hand::new();
+        hand.add_many(cards.to_vec());
+        hand
+    }
+
+    #[test]
+    fn matches_single_cards_and_pairs_summing_to_the_target() {
+        let hand = hand_of(&[red(5), blue(2), red(3), blue(4)]);
+        assert_eq!(matches(&hand, &red(5)), vec![vec![0], vec![1, 2]]);
+        assert!(matches(&hand, &red(10)).is_empty());
+    }
+
+    #[test]
+    fn wild_dos_counts_as_a_two() {
+        let hand = hand_of(&[Card::new_wild(Face::Number(2)), red(3)]);
+        assert_eq!(matches(&hand, &blue(5)), vec![vec![0, 1]]);
+        assert_eq!(matches(&hand, &blue(2)), vec![vec![0]]);
+    }
+
+    #[test]
+    fn nothing_matches_a_target_without_a_number() {
+        let hand = hand_of(&[red(5), blue(2)]);
+        assert!(matches(&hand, &Card::new_wild(Face::Wild)).is_empty());
+    }
+
+    #[test]
+    fn color_bonus_needs_every_card_to_match_the_target_color() {
+        assert_eq!(color_bonus(&red(5), &[red(5)]), ColorBonus::Single);
+        assert_eq!(color_bonus(&red(5), &[red(2), red(3)]), ColorBonus::Double);
+        assert_eq!(color_bonus(&red(5), &[red(2), blue(3)]), ColorBonus::None);
+        assert_eq!(color_bonus(&red(5), &[blue(5)]), ColorBonus::None);
+    }
+
+    #[test]
+    fn wild_dos_needs_a_declared_color_for_a_bonus() {
+        let mut wild = Card::new_wild(Face::Number(2));
+        assert_eq!(color_bonus(&red(2), &[wild]), ColorBonus::None);
+        wild.assign_color(ColorSuite::Red);
+        assert_eq!(color_bonus(&red(2), &[wild]), ColorBonus::Single);
+    }
+
+    #[test]
+    fn a_double_color_match_places_a_card_and_makes_the_others_draw() {
+        let rules = Dos.default_rules();
+        let mut state = GameState::new(3, rules, Dos.cards(&rules), Dos.colors(&rules), Some(7));
+        state.hands[0] = hand_of(&[red(3), red(1), blue(9)]);
+        state.table = Table::new();
+        state.table.add_target(red(4));
+
+        let pair = Action::Play {
+            target: 0,
+            cards: vec![red(3), red(1)],
+            color: None,
+        };
+        assert!(Dos.legal_moves(&state, 0).contains(&pair));
+        assert_eq!(Dos.apply(&mut state, 0, pair), TurnStatus::Continue);
+        assert_eq!(state.turn.placements, 1);
+        assert_eq!(state.turn.penalty, 1);
+
+        let place = Action::Place {
+            card: blue(9),
+            color: None,
+        };
+        assert_eq!(Dos.legal_moves(&state, 0), vec![place.clone()]);
+        Dos.apply(&mut state, 0, place);
+        assert_eq!(Dos.legal_moves(&state, 0), vec![Action::Pass]);
+        assert_eq!(Dos.apply(&mut state, 0, Action::Pass), TurnStatus::Over);
+
+        Dos.end_turn(&mut state, 0);
+        assert!(state.hands[0].is_empty());
+        assert_eq!(state.hands[1].len(), 1);
+        assert_eq!(state.hands[2].len(), 1);
+        assert!(state.table.tops().contains(&blue(9)));
+        assert_eq!(state.table.len(), Dos::ROW_SIZE);
+    }
+
+    #[test]
+    fn a_failed_draw_forces_a_card_onto_the_center_row() {
+        let rules = Dos.default_rules();
+        let mut state = GameState::new(2, rules, Dos.cards(&rules), Dos.colors(&rules), Some(8));
+        state.hands[0] = hand_of(&[blue(9)]);
+        state.hands[1] = hand_of(&[blue(1), blue(3)]);
+        state.dealer.replace_cards(vec![red(1), blue(10)]);
+        state.table = Table::new();
+        state.table.add_target(red(4));
+        state.table.add_target(red(3));
+
+        assert_eq!(Dos.legal_moves(&state, 0), vec![Action::Draw]);
+        assert_eq!(Dos.apply(&mut state, 0, Action::Draw), TurnStatus::Continue);
+        let place = Action::Place {
+            card: blue(10),
+            color: None,
+        };
+        let moves = Dos.legal_moves(&state, 0);
+        assert_eq!(moves.len(), 2);
+        assert!(moves.contains(&place));
+
+        Dos.apply(&mut state, 0, place);
+        assert_eq!(Dos.legal_moves(&state, 0), vec![Action::Pass]);
+        assert_eq!(Dos.apply(&mut state, 0, Action::Pass), TurnStatus::Over);
+        Dos.end_turn(&mut state, 0);
+        assert_eq!(state.hands[0].len(), 1);
+        assert_eq!(state.table.tops(), vec![red(4), red(3), blue(10)]);
+    }
+}
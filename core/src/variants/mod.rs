@@ -0,0 +1,87 @@
+mod classic;
+mod crazy_eights;
+mod dos;
+
+use std::{error::Error, fmt};
+
+use crate::{action::Action, card::Card, color::ColorSuite, rules::Rules, state::GameState};
+
+pub use classic::Classic;
+pub use crazy_eights::CrazyEights;
+pub use dos::Dos;
+
+/// Whether a player's turn goes on after an action
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum TurnStatus {
+    Continue,
+    Over,
+}
+
+/// A game the engine can run.
+/// A ruleset decides what the deck holds, which moves are legal,
+/// what those moves do and when the game is over.
+pub trait Ruleset: Send + Sync {
+    /// The name this ruleset is selected by
+    fn name(&self) -> &'static str;
+
+    /// The rules this game is played with unless told otherwise
+    fn default_rules(&self) -> Rules {
+        Rules::default()
+    }
+
+    /// Returns every card the game is dealt from, unshuffled
+    fn cards(&self, rules: &Rules) -> Vec<Card>;
+
+    /// Returns the colors wild cards can be declared as
+    fn colors(&self, rules: &Rules) -> &'static [ColorSuite];
+
+    /// Deals the hands and lays out the table before the first turn
+    fn setup(&self, state: &mut GameState);
+
+    /// Returns every move `seat` may make at this point of their turn.
+    /// An empty list ends the turn.
+    fn legal_moves(&self, state: &GameState, seat: usize) -> Vec<Action>;
+
+    /// Carries out a legal move made by `seat`
+    fn apply(&self, state: &mut GameState, seat: usize, action: Action) -> TurnStatus;
+
+    /// Cleans up after the turn of `seat` is over
+    fn end_turn(&self, _state: &mut GameState, _seat: usize) {}
+
+    /// Returns the seat that won, if the game is over
+    fn winner(&self, state: &GameState) -> Option<usize> {
+        state.hands.iter().position(|hand| hand.is_empty())
+    }
+}
+
+/// Every ruleset that can be selected by name
+const RULESETS: [&dyn Ruleset; 3] = [&Classic, &CrazyEights, &Dos];
+
+/// Looks up a ruleset by its name, ignoring case
+pub fn by_name(name: &str) -> Option<&'static dyn Ruleset> {
+    RULESETS
+        .into_iter()
+        .find(|ruleset| ruleset.name().eq_ignore_ascii_case(name))
+}
+
+/// Returns the names of every ruleset that can be selected
+pub fn names() -> Vec<&'static str> {
+    RULESETS.iter().map(|ruleset| ruleset.name()).collect()
+}
+
+/// The error returned when no ruleset has the requested name
+#[derive(Debug)]
+pub struct UnknownVariant(pub String);
+
+impl fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown variant {}, expected one of {}",
+            self.0,
+            names().join(", ")
+        )
+    }
+}
+
+impl Error for UnknownVariant {}
@@ -0,0 +1,174 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    card::Card, color::ColorSuite, hand::Hand, reversible_ring::Direction, rules::Rules,
+    simulation::Simulation, state::GameState, variants::Ruleset,
+};
+
+/// What a single player can see of the game when making a decision.
+/// Holds every piece of public information along with the player's own hand,
+/// but never another player's hand or the order of the deck.
+pub struct PlayerView<'a> {
+    /// Only handed out with the hidden cards shuffled, see `determinize`
+    state: &'a GameState,
+    ruleset: &'static dyn Ruleset,
+    seat: usize,
+    hand: &'a Hand,
+    rules: &'a Rules,
+    colors: &'static [ColorSuite],
+    hand_sizes: Vec<usize>,
+    direction: Direction,
+    next_seat: usize,
+    stack_count: usize,
+    tops: Vec<Card>,
+    history: &'a [Card],
+    composition: &'a [Card],
+    cards_left: usize,
+    drawn: usize,
+
+    /// Whether the game lets this player see the hidden cards, see `Options::perfect_information`
+    perfect_information: bool,
+}
+
+impl<'a> PlayerView<'a> {
+    /// Builds the view of `seat` from the full game state
+    pub fn new(ruleset: &'static dyn Ruleset, state: &'a GameState, seat: usize) -> Self {
+        Self {
+            state,
+            ruleset,
+            seat,
+            hand: &state.hands[seat],
+            rules: &state.rules,
+            colors: state.colors,
+            hand_sizes: state.hands.iter().map(Hand::len).collect(),
+            direction: state.order.direction(),
+            next_seat: *state.order.peek_next(),
+            stack_count: state.stack_count,
+            tops: state.table.tops(),
+            history: state.table.history(),
+            composition: &state.composition,
+            cards_left: state.dealer.cards_left(),
+            drawn: state.turn.drawn,
+            perfect_information: false,
+        }
+    }
+
+    /// Lets the view reveal the hidden cards when the game allows it.
+    /// Only the engine decides this, so a strategy can't grant itself perfect information.
+    pub(crate) fn with_perfect_information(mut self, allowed: bool) -> Self {
+        self.perfect_information = allowed;
+        self
+    }
+
+    /// The seat of the player this view belongs to
+    pub fn seat(&self) -> usize {
+        self.seat
+    }
+
+    pub fn hand(&self) -> &Hand {
+        self.hand
+    }
+
+    pub fn rules(&self) -> &Rules {
+        self.rules
+    }
+
+    /// The colors wild cards can be declared as
+    pub fn colors(&self) -> &[ColorSuite] {
+        self.colors
+    }
+
+    /// The amount of cards in every hand, indexed by seat
+    pub fn hand_sizes(&self) -> &[usize] {
+        &self.hand_sizes
+    }
+
+    /// The amount of players in the game
+    pub fn players(&self) -> usize {
+        self.hand_sizes.len()
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The seat that plays after the current turn, before any card takes effect
+    pub fn next_seat(&self) -> usize {
+        self.next_seat
+    }
+
+    /// The amount of cards stacked up by plus cards for the next player to draw
+    pub fn stack_count(&self) -> usize {
+        self.stack_count
+    }
+
+    /// The top card of the discard pile in single pile games
+    pub fn top(&self) -> &Card {
+        &self.tops[0]
+    }
+
+    /// The top card of every target on the table
+    pub fn tops(&self) -> &[Card] {
+        &self.tops
+    }
+
+    /// Every card that was laid face up this game, oldest first
+    pub fn history(&self) -> &[Card] {
+        self.history
+    }
+
+    /// Every card on the table that has not been shuffled back into the deck.
+    /// Wild cards keep the colors declared for them.
+    pub fn face_up(&self) -> Vec<Card> {
+        self.state.table.face_up()
+    }
+
+    /// Every card the game is played with, as dealt
+    pub fn composition(&self) -> &[Card] {
+        self.composition
+    }
+
+    /// The amount of cards left in the deck
+    pub fn cards_left(&self) -> usize {
+        self.cards_left
+    }
+
+    /// The amount of cards this player has drawn so far this turn
+    pub fn drawn(&self) -> usize {
+        self.drawn
+    }
+
+    /// Returns a copy of the game as it really is, hidden cards included,
+    /// or nothing unless the game was run with perfect information
+    pub(crate) fn reveal(&self) -> Option<Simulation> {
+        if !self.perfect_information {
+            return None;
+        }
+        let mut state = self.state.clone();
+        state.take_events();
+        Some(Simulation::new(self.ruleset, state, self.seat))
+    }
+
+    /// Returns a copy of the game where every card this player can't see
+    /// is dealt again at random, keeping the size of every hand.
+    /// The copy can be played forward to see how the current decision might turn out.
+    pub fn determinize<R: Rng + ?Sized>(&self, rng: &mut R) -> Simulation {
+        let mut state = self.state.clone();
+        let mut hidden: Vec<Card> = state.dealer.cards().to_vec();
+        for (seat, hand) in state.hands.iter().enumerate() {
+            if seat != self.seat {
+                hidden.extend(hand);
+            }
+        }
+        hidden.shuffle(rng);
+        for seat in (0..state.hands.len()).filter(|&seat| seat != self.seat) {
+            let mut hand = Hand::new();
+            hand.add_many(hidden.split_off(hidden.len() - state.hands[seat].len()));
+            state.hands[seat] = hand;
+        }
+        state.dealer.replace_cards(hidden);
+        state.reseed(rng.gen());
+        state.take_events();
+        Simulation::new(self.ruleset, state, self.seat)
+    }
+}